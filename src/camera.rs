@@ -1,6 +1,22 @@
+use crate::display::DisplaySettings;
+use crate::newgame::RoomTileMap;
+use crate::pixel_perfect::PixelZoom;
 use crate::prelude::*;
+use crate::settings::{ReflectSettings, ReflectSettingsPlugin, SettingField, SettingKind};
+use bevy::input::mouse::{AccumulatedMouseScroll, MouseScrollUnit};
 use bevy::prelude::ops::powf;
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_ecs_tilemap::prelude::*;
+
+/// The mouse buttons that pan the camera when dragged.
+const DRAG_PAN_BUTTONS: [MouseButton; 2] = [MouseButton::Middle, MouseButton::Right];
+/// How close to the edge of the window the cursor has to be to scroll, in logical pixels.
+const EDGE_SCROLL_MARGIN: f32 = 8.0;
+/// How far one line of the mouse wheel zooms, as a fraction of a second of holding a zoom control.
+const WHEEL_ZOOM_STEP: f32 = 0.1;
+/// How many pixels of scrolling on a touchpad count as one line of a mouse wheel.
+const PIXELS_PER_SCROLL_LINE: f32 = 100.0;
 
 /// The plugin to enable the camera
 pub struct CameraPlugin;
@@ -9,27 +25,85 @@ impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<MainCamera>()
             .register_type::<CameraMovementSettings>()
-            .init_resource::<CameraMovementSettings>()
+            .register_type::<CameraTarget>()
+            .register_type::<CameraMouseSettings>()
+            .add_plugins(ReflectSettingsPlugin::<CameraMovementSettings>::default())
+            .add_plugins(ReflectSettingsPlugin::<CameraMouseSettings>::default())
+            .init_resource::<CameraRoom>()
             .add_systems(Startup, camera_setup)
+            .configure_sets(
+                PostUpdate,
+                CameraSystems
+                    .after(bevy::render::camera::camera_system)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(OnExit(GameState::Game), camera_reset)
             .add_systems(
                 PostUpdate,
-                (pause_game, (camera_movement, camera_zoom))
+                (
+                    restore_view,
+                    camera_zoom,
+                    track_room,
+                    camera_pan.run_if(not(following)),
+                    camera_movement,
+                    offset_view,
+                )
                     .chain()
-                    .run_if(in_state(GameState::Game))
-                    .after(bevy::render::camera::camera_system),
+                    .run_if(in_state(PauseState::Running))
+                    .in_set(CameraSystems),
             );
     }
 }
 
+/// The systems moving and zooming the [`MainCamera`], after which its [`Transform`]
+/// is where the world is drawn from this frame.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CameraSystems;
+
 /// The marker component to signify a camera is the main rendering camera
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct MainCamera;
+#[require(ViewEffects, ViewOffset)]
+pub struct MainCamera;
+
+/// Moves and zooms where the [`MainCamera`] is drawn from, without moving the camera.
+///
+/// This is for effects such as shaking, see [`crate::camera_effects`].
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default, Debug, PartialEq)]
+pub struct ViewEffects {
+    /// How far to draw the world from, in world units.
+    pub offset: Vec2,
+    /// How much closer to zoom, where `0.5` shows the world half again as big.
+    /// Has no effect when pixel-perfect, which only zooms in whole steps.
+    pub zoom: f32,
+}
+
+/// How the [`MainCamera`] was moved and zoomed from where it is, by its [`ViewEffects`]
+/// and snapping it to a whole pixel, which is undone before moving it again so
+/// it can move by less than a pixel.
+#[derive(Component, Debug)]
+struct ViewOffset {
+    translation: Vec2,
+    scale: f32,
+}
+
+impl Default for ViewOffset {
+    fn default() -> Self {
+        Self {
+            translation: Vec2::ZERO,
+            scale: 1.0,
+        }
+    }
+}
 
 /// The camera movement settings for the [`MainCamera`]
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
-struct CameraMovementSettings {
+pub struct CameraMovementSettings {
+    /// Whether the camera follows the [`CameraTarget`] or is panned by the player.
+    mode: CameraMode,
+
     /// The movement speed of the camera in in-game pixels per second
     move_speed: f32,
 
@@ -37,86 +111,667 @@ struct CameraMovementSettings {
     /// This uses the inverse of the speed when zooming in.
     zoom_speed: f32,
 
-    /// The allowed area the camera can be in, where
-    /// each column is the range of movement for a direction.
-    /// The first column is the `x` direction, and the second is `y`
-    move_area: Mat2,
-
     /// The bounds of the zoom, `x` being the lower bound and `y` being the upper bound.
     zoom_limit: Vec2,
+
+    /// How quickly the camera catches up to its target, as the rate the
+    /// distance decays at each second. Higher is snappier.
+    follow_damping: f32,
+
+    /// How far ahead of the target the camera looks, in seconds of the target's movement.
+    look_ahead: f32,
+
+    /// The furthest the camera looks ahead of the target, in in-game pixels.
+    max_look_ahead: f32,
+
+    /// The same as [`Self::follow_damping`], but used while moving to a new room.
+    transition_damping: f32,
 }
 
 impl Default for CameraMovementSettings {
     fn default() -> Self {
         Self {
+            mode: CameraMode::Follow,
             move_speed: 300.0,
             zoom_speed: 4.0,
-            move_area: Mat2::from_cols_array_2d(&[[-200.0, 200.0]; 2]),
             zoom_limit: Vec2::new(0.25, 1.0),
+            follow_damping: 6.0,
+            look_ahead: 0.3,
+            max_look_ahead: 96.0,
+            transition_damping: 3.0,
         }
     }
 }
 
-/// Sets up the main camera and it's settings
+impl ReflectSettings for CameraMovementSettings {
+    const DB_PREFIX: &'static str = "camera";
+    const FIELDS: &'static [SettingField] = &[
+        SettingField {
+            path: "mode",
+            label: "camera.mode",
+            kind: SettingKind::Choice(&[
+                ("Follow", "camera_mode.follow"),
+                ("Free", "camera_mode.free"),
+            ]),
+        },
+        SettingField {
+            path: "move_speed",
+            label: "camera.move_speed",
+            kind: SettingKind::Number {
+                min: 100.0,
+                max: 1000.0,
+                step: 25.0,
+                format: |value| format!("{value:.0}"),
+            },
+        },
+        SettingField {
+            path: "zoom_speed",
+            label: "camera.zoom_speed",
+            kind: SettingKind::Number {
+                min: 1.5,
+                max: 10.0,
+                step: 0.5,
+                format: |value| format!("{value:.1}x"),
+            },
+        },
+        SettingField {
+            path: "follow_damping",
+            label: "camera.follow_damping",
+            kind: SettingKind::Number {
+                min: 1.0,
+                max: 20.0,
+                step: 0.5,
+                format: |value| format!("{value:.1}"),
+            },
+        },
+        SettingField {
+            path: "look_ahead",
+            label: "camera.look_ahead",
+            kind: SettingKind::Number {
+                min: 0.0,
+                max: 1.0,
+                step: 0.05,
+                format: |value| format!("{value:.2}s"),
+            },
+        },
+    ];
+}
+
+/// Which ways of moving the camera with the mouse the player wants.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Debug, Clone, PartialEq)]
+pub struct CameraMouseSettings {
+    /// Pan by dragging with the middle or right mouse button.
+    pub drag_pan: bool,
+    /// Pan by holding the cursor at the edge of the window.
+    pub edge_scroll: bool,
+    /// Zoom with the mouse wheel, towards the point under the cursor.
+    pub wheel_zoom: bool,
+}
+
+impl Default for CameraMouseSettings {
+    fn default() -> Self {
+        Self {
+            drag_pan: true,
+            // Off by default, as it pans whenever the cursor leaves a windowed game.
+            edge_scroll: false,
+            wheel_zoom: true,
+        }
+    }
+}
+
+impl ReflectSettings for CameraMouseSettings {
+    const DB_PREFIX: &'static str = "camera";
+    const FIELDS: &'static [SettingField] = &[
+        SettingField {
+            path: "drag_pan",
+            label: "camera.drag_pan",
+            kind: SettingKind::Toggle,
+        },
+        SettingField {
+            path: "edge_scroll",
+            label: "camera.edge_scroll",
+            kind: SettingKind::Toggle,
+        },
+        SettingField {
+            path: "wheel_zoom",
+            label: "camera.wheel_zoom",
+            kind: SettingKind::Toggle,
+        },
+    ];
+}
+
+/// How the [`MainCamera`] decides where to go.
+#[derive(Reflect, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Default, Debug, PartialEq)]
+pub enum CameraMode {
+    /// Follows the [`CameraTarget`] around its room,
+    /// or pans from input when there is no target.
+    #[default]
+    Follow,
+    /// Pans from input anywhere in the rooms.
+    Free,
+}
+
+/// The marker component for the entity the [`MainCamera`] follows.
+///
+/// Only one entity should have this at a time.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct CameraTarget;
+
+/// The room the [`MainCamera`] is kept inside of.
+#[derive(Resource, Default, Debug)]
+struct CameraRoom {
+    room: Option<Entity>,
+    /// Whether the camera is still moving over from the previous room.
+    transitioning: bool,
+}
+
+/// The tilemaps of the rooms, with what is needed for their [`room_bounds`].
+type RoomQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static TilemapSize,
+        &'static TilemapTileSize,
+        &'static TilemapType,
+        &'static GlobalTransform,
+    ),
+    With<RoomTileMap>,
+>;
+
+/// The projection the [`MainCamera`] starts every game with.
+fn main_camera_projection() -> Projection {
+    Projection::Orthographic(OrthographicProjection {
+        scaling_mode: bevy::render::camera::ScalingMode::WindowSize,
+        scale: 0.5,
+        ..OrthographicProjection::default_2d()
+    })
+}
+
+/// Sets up the main camera
 fn camera_setup(mut commands: Commands) {
     commands.spawn((
         MainCamera,
         Camera2d,
-        Projection::Orthographic(OrthographicProjection {
-            scaling_mode: bevy::render::camera::ScalingMode::WindowSize,
-            scale: 0.5,
-            ..OrthographicProjection::default_2d()
-        }),
+        main_camera_projection(),
         Transform::IDENTITY,
     ));
 }
 
-fn pause_game(mut commands: Commands, input: Res<ControlState>) {
-    if input.just_pressed(Control::Pause) {
-        commands.set_state(GameState::Menu);
+/// Whether the camera is following a [`CameraTarget`], rather than being panned by the player.
+fn following(
+    settings: Res<CameraMovementSettings>,
+    targets: Query<(), With<CameraTarget>>,
+) -> bool {
+    settings.mode == CameraMode::Follow && !targets.is_empty()
+}
+
+/// Puts the camera back where it started, so the next game doesn't
+/// begin wherever the last one was left.
+fn camera_reset(
+    camera: Single<
+        (
+            &mut Transform,
+            &mut Projection,
+            &mut ViewOffset,
+            &mut ViewEffects,
+        ),
+        With<MainCamera>,
+    >,
+    display: Res<DisplaySettings>,
+    mut zoom: ResMut<PixelZoom>,
+    mut room: ResMut<CameraRoom>,
+) {
+    let (mut transform, mut projection, mut offset, mut effects) = camera.into_inner();
+    *transform = Transform::IDENTITY;
+    *offset = ViewOffset::default();
+    *effects = ViewEffects::default();
+    *zoom = PixelZoom::default();
+    // The canvas is drawn with a world unit for each of its pixels.
+    if !display.pixel_perfect {
+        *projection = main_camera_projection();
+    }
+    *room = CameraRoom::default();
+}
+
+/// The world units for each logical pixel of the window.
+fn world_per_pixel(
+    projection2d: &OrthographicProjection,
+    display: &DisplaySettings,
+    zoom: PixelZoom,
+    scale_factor: f32,
+) -> f32 {
+    if display.pixel_perfect {
+        zoom.world_per_pixel(scale_factor)
+    } else {
+        projection2d.scale
     }
 }
 
-/// Controls the camera's translational movement based
-/// on user input.
-fn camera_movement(
-    mut transform: Single<&mut Transform, With<MainCamera>>,
+/// Puts the camera back where it is, undoing its [`ViewOffset`].
+fn restore_view(
+    camera: Single<(&mut Transform, &mut Projection, &mut ViewOffset), With<MainCamera>>,
+) {
+    let (mut transform, mut projection, mut offset) = camera.into_inner();
+    let Projection::Orthographic(ref mut projection2d) = *projection else {
+        unreachable!("Only Orthographic Projection is supported!");
+    };
+
+    if offset.translation != Vec2::ZERO {
+        transform.translation -= offset.translation.extend(0.0);
+    }
+    if offset.scale != 1.0 {
+        projection2d.scale /= offset.scale;
+    }
+    *offset = ViewOffset::default();
+}
+
+/// Moves the camera by its [`ViewEffects`], snapping it to a whole pixel of the
+/// art when pixel-perfect so the pixels of the art line up with those of the canvas.
+fn offset_view(
+    camera: Single<
+        (
+            &mut Transform,
+            &mut Projection,
+            &mut ViewOffset,
+            &ViewEffects,
+        ),
+        With<MainCamera>,
+    >,
+    display: Res<DisplaySettings>,
+) {
+    let (mut transform, mut projection, mut offset, effects) = camera.into_inner();
+    let Projection::Orthographic(ref mut projection2d) = *projection else {
+        unreachable!("Only Orthographic Projection is supported!");
+    };
+
+    let position = transform.translation.xy();
+    let mut drawn = position + effects.offset;
+    if display.pixel_perfect {
+        drawn = drawn.round();
+    }
+    if drawn != position {
+        offset.translation = drawn - position;
+        transform.translation = drawn.extend(transform.translation.z);
+    }
+
+    if !display.pixel_perfect && effects.zoom != 0.0 {
+        offset.scale = 1.0 / (1.0 + effects.zoom);
+        projection2d.scale *= offset.scale;
+    }
+}
+
+/// The area a room's tilemap covers in the world.
+///
+/// The tilemaps are anchored at their center, so this is centered on the tilemap.
+fn room_bounds(
+    size: &TilemapSize,
+    tile_size: &TilemapTileSize,
+    map_type: &TilemapType,
+    transform: &GlobalTransform,
+) -> Rect {
+    let size = Vec2::new(size.x as f32, size.y as f32);
+    let tile = Vec2::new(tile_size.x, tile_size.y);
+
+    // Hexagons of neighbouring rows (or columns) overlap by a quarter of a tile.
+    let extent = match map_type {
+        TilemapType::Hexagon(
+            HexCoordSystem::Row | HexCoordSystem::RowEven | HexCoordSystem::RowOdd,
+        ) => Vec2::new(size.x * tile.x, tile.y + (size.y - 1.0) * tile.y * 0.75),
+        TilemapType::Hexagon(
+            HexCoordSystem::Column | HexCoordSystem::ColumnEven | HexCoordSystem::ColumnOdd,
+        ) => Vec2::new(tile.x + (size.x - 1.0) * tile.x * 0.75, size.y * tile.y),
+        _ => size * tile,
+    };
+
+    Rect::from_center_size(transform.translation().xy(), extent)
+}
+
+/// Keeps track of which room the camera should stay in, which is the
+/// one its target is in, starting a transition when that changes.
+fn track_room(
     settings: Res<CameraMovementSettings>,
+    mut current: ResMut<CameraRoom>,
+    camera: Single<&Transform, With<MainCamera>>,
+    target: Option<Single<&GlobalTransform, With<CameraTarget>>>,
+    rooms: RoomQuery,
+) {
+    let target = target
+        .filter(|_| settings.mode == CameraMode::Follow)
+        .map(|target| target.translation().xy());
+    let focus = target.unwrap_or(camera.translation.xy());
+
+    let room = rooms
+        .iter()
+        .find(|(_, size, tile_size, map_type, transform)| {
+            room_bounds(size, tile_size, map_type, transform).contains(focus)
+        })
+        .map(|(entity, ..)| entity)
+        // Stay with the last room while between rooms, as long as it is still around.
+        .or(current.room.filter(|room| rooms.contains(*room)));
+
+    if current.room != room {
+        // Moving into the first room is a jump, not a transition,
+        // and panning by hand isn't kept to a room.
+        current.transitioning = target.is_some() && current.room.is_some() && room.is_some();
+        current.room = room;
+    }
+}
+
+/// Moves `position` as close to `goal` as it can while keeping
+/// the view, which is `half_view` each way, inside of `bounds`.
+/// Rooms smaller than the view are centered instead.
+fn clamp_view(goal: Vec2, half_view: Vec2, bounds: Rect) -> Vec2 {
+    let inner = Rect {
+        min: bounds.min + half_view,
+        max: bounds.max - half_view,
+    };
+    Vec2::new(
+        if inner.min.x <= inner.max.x {
+            goal.x.clamp(inner.min.x, inner.max.x)
+        } else {
+            bounds.center().x
+        },
+        if inner.min.y <= inner.max.y {
+            goal.y.clamp(inner.min.y, inner.max.y)
+        } else {
+            bounds.center().y
+        },
+    )
+}
+
+/// Pans the camera from user input: the movement controls,
+/// and dragging or edge scrolling with the mouse.
+fn camera_pan(
+    camera: Single<(&mut Transform, &Projection), With<MainCamera>>,
+    settings: Res<CameraMovementSettings>,
+    mouse_settings: Res<CameraMouseSettings>,
+    display: Res<DisplaySettings>,
+    zoom: Res<PixelZoom>,
     input: Res<ControlState>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Query<&Window, With<PrimaryWindow>>,
     time: Res<Time>,
+    mut last_cursor: Local<Option<Vec2>>,
 ) {
-    let movement = Vec2::Y * input.pressed(Control::MoveUp) as u8 as f32
+    let (mut transform, projection) = camera.into_inner();
+    let Projection::Orthographic(projection2d) = projection else {
+        unreachable!("Only Orthographic Projection is supported!");
+    };
+
+    let mut direction = Vec2::Y * input.pressed(Control::MoveUp) as u8 as f32
         + Vec2::NEG_Y * input.pressed(Control::MoveDown) as u8 as f32
         + Vec2::NEG_X * input.pressed(Control::MoveLeft) as u8 as f32
         + Vec2::X * input.pressed(Control::MoveRight) as u8 as f32;
 
-    let movement = movement * time.delta_secs() * settings.move_speed;
+    // There is no window, and so no cursor, when running headless.
+    let window = window.single().ok();
+    let cursor = window.and_then(Window::cursor_position);
+
+    if let Some((window, cursor)) = window
+        .zip(cursor)
+        .filter(|(window, _)| mouse_settings.edge_scroll && window.focused)
+    {
+        let size = window.size();
+        // The cursor's y goes down the window, while the world's goes up.
+        direction.x += (cursor.x >= size.x - EDGE_SCROLL_MARGIN) as u8 as f32
+            - (cursor.x < EDGE_SCROLL_MARGIN) as u8 as f32;
+        direction.y += (cursor.y < EDGE_SCROLL_MARGIN) as u8 as f32
+            - (cursor.y >= size.y - EDGE_SCROLL_MARGIN) as u8 as f32;
+    }
+
+    let mut movement = direction * time.delta_secs() * settings.move_speed;
 
-    transform.translation = (transform.translation.xy() + movement)
-        .clamp(settings.move_area.row(0), settings.move_area.row(1))
-        .extend(transform.translation.z);
+    let dragging = mouse_settings.drag_pan && mouse.any_pressed(DRAG_PAN_BUTTONS);
+    if let (true, Some(window), Some(cursor), Some(last)) = (dragging, window, cursor, *last_cursor)
+    {
+        // Move the world along with the cursor, as if it were being held.
+        let dragged = cursor - last;
+        movement += Vec2::new(-dragged.x, dragged.y)
+            * world_per_pixel(projection2d, &display, *zoom, window.scale_factor());
+    }
+    *last_cursor = cursor.filter(|_| dragging);
+
+    if movement != Vec2::ZERO {
+        transform.translation += movement.extend(0.0);
+    }
+}
+
+/// Moves the camera after its [`CameraTarget`], keeping it inside of the rooms.
+fn camera_movement(
+    camera: Single<(&mut Transform, &Projection), With<MainCamera>>,
+    target: Option<Single<&GlobalTransform, With<CameraTarget>>>,
+    rooms: RoomQuery,
+    settings: Res<CameraMovementSettings>,
+    mut room: ResMut<CameraRoom>,
+    time: Res<Time>,
+    mut last_target: Local<Option<Vec2>>,
+    mut velocity: Local<Vec2>,
+) {
+    let (mut transform, projection) = camera.into_inner();
+    let Projection::Orthographic(projection2d) = projection else {
+        unreachable!("Only Orthographic Projection is supported!");
+    };
+
+    let delta = time.delta_secs();
+    let position = transform.translation.xy();
+    let half_view = projection2d.area.half_size();
+
+    let target = target
+        .filter(|_| settings.mode == CameraMode::Follow)
+        .map(|target| target.translation().xy());
+
+    // Smooth out the target's velocity, so the look-ahead doesn't jitter.
+    match (target, *last_target) {
+        (Some(target), Some(last)) if delta > 0.0 => {
+            velocity.smooth_nudge(&((target - last) / delta), settings.follow_damping, delta);
+        }
+        _ => *velocity = Vec2::ZERO,
+    }
+    *last_target = target;
+
+    let bounds = |entity: Option<Entity>| {
+        rooms
+            .iter()
+            .filter(|(room, ..)| entity.is_none_or(|entity| entity == *room))
+            .map(|(_, size, tile_size, map_type, transform)| {
+                room_bounds(size, tile_size, map_type, transform)
+            })
+            .reduce(|a, b| a.union(b))
+    };
+
+    // Without a target the camera has already been panned by `camera_pan`.
+    let goal = match target {
+        Some(target) => {
+            let look_ahead =
+                (*velocity * settings.look_ahead).clamp_length_max(settings.max_look_ahead);
+            target + look_ahead
+        }
+        None => position,
+    };
+
+    // Panning by hand can go anywhere in the rooms, while following stays in the target's room.
+    let goal = match bounds(target.and(room.room)) {
+        Some(bounds) => clamp_view(goal, half_view, bounds),
+        None => goal,
+    };
+
+    let damping = if room.transitioning {
+        settings.transition_damping
+    } else {
+        settings.follow_damping
+    };
+
+    let mut next = position;
+    if target.is_some() || room.transitioning {
+        next.smooth_nudge(&goal, damping, delta);
+    } else {
+        next = goal;
+    }
+
+    if room.transitioning && next.distance(goal) < 1.0 {
+        room.transitioning = false;
+    }
+
+    if next != position {
+        transform.translation = next.extend(transform.translation.z);
+    }
 }
 
 /// Controls the camera's zoom based on user input.
+///
+/// The mouse wheel zooms towards the cursor, keeping the point under it in place.
 fn camera_zoom(
-    mut projection: Single<&mut Projection, With<MainCamera>>,
+    camera: Single<(&mut Transform, &mut Projection), With<MainCamera>>,
     settings: Res<CameraMovementSettings>,
+    mouse_settings: Res<CameraMouseSettings>,
+    display: Res<DisplaySettings>,
+    window: Query<&Window, With<PrimaryWindow>>,
     input: Res<ControlState>,
+    scroll: Res<AccumulatedMouseScroll>,
     time: Res<Time>,
+    mut zoom: ResMut<PixelZoom>,
 ) {
-    let Projection::Orthographic(ref mut projection2d) = **projection else {
+    let (mut transform, mut projection) = camera.into_inner();
+    let Projection::Orthographic(ref mut projection2d) = *projection else {
         unreachable!("Only Orthographic Projection is supported!");
     };
 
-    let scale = projection2d.scale
-        * powf(
-            powf(settings.zoom_speed, time.delta_secs()),
-            input.pressed(Control::ZoomIn) as u8 as f32,
-        )
-        * powf(
-            powf(1.0 / settings.zoom_speed, time.delta_secs()),
-            input.pressed(Control::ZoomOut) as u8 as f32,
+    let window = window.single().ok();
+
+    // The lines scrolled up, which zoom in.
+    let wheel = match scroll.unit {
+        _ if !mouse_settings.wheel_zoom => 0.0,
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / PIXELS_PER_SCROLL_LINE,
+    };
+
+    let scale_factor = window.map(Window::scale_factor).unwrap_or(1.0);
+    let old_scale = world_per_pixel(projection2d, &display, *zoom, scale_factor);
+
+    if display.pixel_perfect {
+        pixel_perfect_zoom(&mut zoom, &settings, &input, wheel, scale_factor);
+    } else {
+        let scale = projection2d.scale
+            * powf(
+                powf(settings.zoom_speed, time.delta_secs()),
+                input.pressed(Control::ZoomIn) as u8 as f32,
+            )
+            * powf(
+                powf(1.0 / settings.zoom_speed, time.delta_secs()),
+                input.pressed(Control::ZoomOut) as u8 as f32,
+            )
+            * powf(1.0 / settings.zoom_speed, wheel * WHEEL_ZOOM_STEP);
+
+        projection2d.scale = scale.clamp(settings.zoom_limit.x, settings.zoom_limit.y);
+    }
+
+    // Keep the point under the cursor where it is when zooming with the wheel.
+    let cursor = window.and_then(|window| Some((window, window.cursor_position()?)));
+    if let (true, Some((window, cursor))) = (wheel != 0.0, cursor) {
+        let offset = cursor - window.size() / 2.0;
+        let new_scale = world_per_pixel(projection2d, &display, *zoom, scale_factor);
+        let offset = Vec2::new(offset.x, -offset.y) * (old_scale - new_scale);
+        if offset != Vec2::ZERO {
+            transform.translation += offset.extend(0.0);
+        }
+    }
+}
+
+/// Zooms in steps where each pixel of the art is a whole number of
+/// physical pixels, as the art is nearest sampled and would otherwise
+/// have pixels of uneven sizes.
+fn pixel_perfect_zoom(
+    pixel_zoom: &mut ResMut<PixelZoom>,
+    settings: &CameraMovementSettings,
+    input: &ControlState,
+    wheel: f32,
+    scale_factor: f32,
+) {
+    // The number of physical pixels for each pixel of art.
+    let min_zoom = (scale_factor / settings.zoom_limit.y).ceil().max(1.0);
+    let max_zoom = (scale_factor / settings.zoom_limit.x).floor().max(min_zoom);
+
+    let mut zoom = pixel_zoom.0 as f32;
+
+    // Zooming in increases the scale, the same as the smooth zoom.
+    if input.just_pressed(Control::ZoomIn) {
+        zoom -= 1.0;
+    }
+    if input.just_pressed(Control::ZoomOut) {
+        zoom += 1.0;
+    }
+    // A step for each notch of the wheel.
+    if wheel != 0.0 {
+        zoom += wheel.signum();
+    }
+
+    let zoom = PixelZoom(zoom.clamp(min_zoom, max_zoom) as u32);
+
+    // Avoid triggering change detection when nothing changed.
+    if **pixel_zoom != zoom {
+        **pixel_zoom = zoom;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_view_keeps_the_view_inside_the_bounds() {
+        let bounds = Rect::new(-100.0, -50.0, 100.0, 50.0);
+        let half_view = Vec2::new(20.0, 10.0);
+
+        // Inside of the bounds the goal is kept.
+        assert_eq!(
+            clamp_view(Vec2::new(10.0, -5.0), half_view, bounds),
+            Vec2::new(10.0, -5.0)
+        );
+        // Past the edges the view stops at them.
+        assert_eq!(
+            clamp_view(Vec2::new(500.0, -500.0), half_view, bounds),
+            Vec2::new(80.0, -40.0)
+        );
+        // A room narrower than the view is centered, while the other axis still clamps.
+        let narrow = Rect::new(0.0, -50.0, 30.0, 50.0);
+        assert_eq!(
+            clamp_view(Vec2::new(500.0, 500.0), half_view, narrow),
+            Vec2::new(15.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn room_bounds_cover_the_tiles() {
+        let size = TilemapSize { x: 3, y: 2 };
+        let tile_size = TilemapTileSize { x: 10.0, y: 10.0 };
+        let transform = GlobalTransform::from_xyz(5.0, -5.0, 0.0);
+
+        let square = room_bounds(&size, &tile_size, &TilemapType::Square, &transform);
+        assert_eq!(
+            square,
+            Rect::from_center_size(Vec2::new(5.0, -5.0), Vec2::new(30.0, 20.0))
         );
 
-    projection2d.scale = scale.clamp(settings.zoom_limit.x, settings.zoom_limit.y);
+        // Each row after the first overlaps the one before by a quarter.
+        let rows = room_bounds(
+            &size,
+            &tile_size,
+            &TilemapType::Hexagon(HexCoordSystem::Row),
+            &transform,
+        );
+        assert_eq!(rows.size(), Vec2::new(30.0, 17.5));
+
+        let columns = room_bounds(
+            &size,
+            &tile_size,
+            &TilemapType::Hexagon(HexCoordSystem::ColumnOdd),
+            &transform,
+        );
+        assert_eq!(columns.size(), Vec2::new(25.0, 20.0));
+        assert_eq!(columns.center(), Vec2::new(5.0, -5.0));
+    }
 }
@@ -0,0 +1,322 @@
+//! Effects on the [`MainCamera`] for feedback from the game, such as shaking
+//! it when something is hit or fading the screen out between rooms.
+//!
+//! Effects are started by sending a [`CameraEffect`], e.g.
+//! ```ignore
+//! effects.write(CameraEffect::Shake { trauma: 0.4 });
+//! ```
+//! The player can turn down the motion with [`DisplaySettings::reduce_motion`],
+//! which stops the camera from shaking and zooming, and softens flashes.
+use crate::camera::{MainCamera, ViewEffects};
+use crate::display::DisplaySettings;
+use crate::prelude::*;
+use bevy::prelude::ops::{exp, sin};
+use bevy::prelude::*;
+
+/// How much trauma wears off each second.
+const TRAUMA_DECAY: f32 = 1.0;
+/// How far the camera moves at full trauma, in world units.
+const MAX_SHAKE_OFFSET: f32 = 12.0;
+/// How quickly the camera shakes, in radians per second of the fastest wave.
+const SHAKE_FREQUENCY: f32 = 40.0;
+/// The rate the zoom punch decays at each second.
+const PUNCH_DECAY: f32 = 8.0;
+/// How strong flashes are when motion is reduced.
+const REDUCED_FLASH_ALPHA: f32 = 0.3;
+/// Draws the overlay over the world, but under the HUD and menus.
+const OVERLAY_Z_INDEX: i32 = -1;
+
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CameraShake>()
+            .register_required_components::<MainCamera, CameraShake>()
+            .add_event::<CameraEffect>()
+            .add_systems(Startup, spawn_overlay)
+            .add_systems(OnExit(GameState::Game), reset_camera_effects)
+            .add_systems(
+                Update,
+                (start_camera_effects, update_shake, update_overlay).chain(),
+            );
+    }
+}
+
+/// Starts an effect on the [`MainCamera`].
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub enum CameraEffect {
+    /// Shakes the camera, adding `trauma` from `0` to `1` which wears off over a second.
+    ///
+    /// The camera shakes by the square of the trauma, so small knocks barely move it
+    /// while big hits add up to a violent shake.
+    Shake { trauma: f32 },
+    /// Zooms the camera in by `strength`, where `0.1` is 10% closer, and springs back.
+    ZoomPunch { strength: f32 },
+    /// Covers the screen in `color`, which fades away over `duration` seconds.
+    Flash { color: Color, duration: f32 },
+    /// Fades the screen out to `color` over `duration` seconds, until a [`Self::FadeIn`].
+    FadeOut { color: Color, duration: f32 },
+    /// Fades the screen back in from a [`Self::FadeOut`] over `duration` seconds.
+    FadeIn { duration: f32 },
+}
+
+/// The shaking and zoom punch of the [`MainCamera`].
+#[derive(Component, Reflect, Default, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default, Debug, PartialEq)]
+pub struct CameraShake {
+    /// From `0` to `1`, see [`CameraEffect::Shake`].
+    pub trauma: f32,
+    /// How much closer the camera is zoomed, see [`CameraEffect::ZoomPunch`].
+    pub punch: f32,
+}
+
+/// A node covering the screen in a color, for flashes and fades.
+#[derive(Component, Debug)]
+struct ScreenOverlay {
+    color: Color,
+    /// The alpha of the color at the start and end of the fade.
+    from: f32,
+    to: f32,
+    elapsed: f32,
+    duration: f32,
+}
+
+impl Default for ScreenOverlay {
+    fn default() -> Self {
+        Self {
+            color: Color::BLACK,
+            from: 0.0,
+            to: 0.0,
+            elapsed: 0.0,
+            duration: 0.0,
+        }
+    }
+}
+
+impl ScreenOverlay {
+    fn alpha(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return self.to;
+        }
+        self.from
+            .lerp(self.to, (self.elapsed / self.duration).clamp(0.0, 1.0))
+    }
+
+    /// Fades from the current alpha to `to`.
+    fn fade(&mut self, color: Color, to: f32, duration: f32) {
+        *self = Self {
+            color,
+            from: self.alpha(),
+            to,
+            elapsed: 0.0,
+            duration,
+        };
+    }
+}
+
+fn spawn_overlay(mut commands: Commands) {
+    commands.spawn((
+        ScreenOverlay::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        GlobalZIndex(OVERLAY_Z_INDEX),
+        Pickable::IGNORE,
+    ));
+}
+
+fn reset_camera_effects(
+    mut shake: Single<&mut CameraShake, With<MainCamera>>,
+    mut overlay: Single<&mut ScreenOverlay>,
+) {
+    **shake = CameraShake::default();
+    **overlay = ScreenOverlay::default();
+}
+
+fn start_camera_effects(
+    mut events: EventReader<CameraEffect>,
+    display: Res<DisplaySettings>,
+    mut shake: Single<&mut CameraShake, With<MainCamera>>,
+    mut overlay: Single<&mut ScreenOverlay>,
+) {
+    for effect in events.read() {
+        match *effect {
+            CameraEffect::Shake { .. } | CameraEffect::ZoomPunch { .. }
+                if display.reduce_motion => {}
+            CameraEffect::Shake { trauma } => {
+                shake.trauma = (shake.trauma + trauma).clamp(0.0, 1.0);
+            }
+            CameraEffect::ZoomPunch { strength } => {
+                shake.punch = shake.punch.max(strength);
+            }
+            CameraEffect::Flash { color, duration } => {
+                let alpha = if display.reduce_motion {
+                    REDUCED_FLASH_ALPHA
+                } else {
+                    1.0
+                };
+                **overlay = ScreenOverlay {
+                    color,
+                    from: alpha,
+                    to: 0.0,
+                    elapsed: 0.0,
+                    duration,
+                };
+            }
+            CameraEffect::FadeOut { color, duration } => overlay.fade(color, 1.0, duration),
+            CameraEffect::FadeIn { duration } => {
+                let color = overlay.color;
+                overlay.fade(color, 0.0, duration);
+            }
+        }
+    }
+}
+
+/// Wears off the shake, and moves the [`ViewEffects`] by what is left of it.
+fn update_shake(
+    camera: Single<(&mut CameraShake, &mut ViewEffects), With<MainCamera>>,
+    display: Res<DisplaySettings>,
+    time: Res<Time>,
+) {
+    let (mut shake, mut effects) = camera.into_inner();
+
+    if display.reduce_motion && *shake != CameraShake::default() {
+        *shake = CameraShake::default();
+    }
+
+    let delta = time.delta_secs();
+    if shake.trauma > 0.0 {
+        shake.trauma = (shake.trauma - TRAUMA_DECAY * delta).max(0.0);
+    }
+    if shake.punch > 0.0 {
+        shake.punch *= exp(-PUNCH_DECAY * delta);
+        // Stop once it can't be seen, rather than decaying forever.
+        if shake.punch < 0.001 {
+            shake.punch = 0.0;
+        }
+    }
+
+    // Waves of unrelated frequencies, which look random without
+    // using up random numbers the game needs to replay the same.
+    let t = time.elapsed_secs() * SHAKE_FREQUENCY;
+    let wobble = Vec2::new(
+        sin(t) + 0.5 * sin(t * 2.31 + 1.7),
+        sin(t * 0.87 + 4.1) + 0.5 * sin(t * 1.93 + 0.6),
+    ) / 1.5;
+
+    let view = ViewEffects {
+        offset: wobble * MAX_SHAKE_OFFSET * shake.trauma * shake.trauma,
+        zoom: shake.punch,
+    };
+    if *effects != view {
+        *effects = view;
+    }
+}
+
+fn update_overlay(overlay: Single<(&mut ScreenOverlay, &mut BackgroundColor)>, time: Res<Time>) {
+    let (mut overlay, mut background) = overlay.into_inner();
+
+    if overlay.elapsed < overlay.duration {
+        overlay.elapsed += time.delta_secs();
+    }
+
+    let color = overlay
+        .color
+        .with_alpha(overlay.color.alpha() * overlay.alpha());
+    if background.0 != color {
+        background.0 = color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fades_start_from_where_the_last_one_is() {
+        let mut overlay = ScreenOverlay::default();
+        overlay.fade(Color::BLACK, 1.0, 2.0);
+        overlay.elapsed = 0.5;
+        assert_eq!(overlay.alpha(), 0.25);
+
+        overlay.fade(Color::WHITE, 0.0, 1.0);
+        assert_eq!(overlay.alpha(), 0.25);
+        overlay.elapsed = 0.5;
+        assert_eq!(overlay.alpha(), 0.125);
+        overlay.elapsed = 2.0;
+        assert_eq!(overlay.alpha(), 0.0);
+    }
+
+    /// An app starting the effects sent to it, with motion reduced or not.
+    fn effects_app(reduce_motion: bool) -> App {
+        let mut app = App::new();
+        app.add_event::<CameraEffect>()
+            .insert_resource(DisplaySettings {
+                reduce_motion,
+                ..default()
+            })
+            .add_systems(Update, start_camera_effects);
+        app.world_mut().spawn((MainCamera, CameraShake::default()));
+        app.world_mut().spawn(ScreenOverlay::default());
+        app
+    }
+
+    fn start(app: &mut App, effect: CameraEffect) {
+        app.world_mut().send_event(effect);
+        app.update();
+    }
+
+    fn shake(app: &mut App) -> CameraShake {
+        *app.world_mut()
+            .query_filtered::<&CameraShake, With<MainCamera>>()
+            .single(app.world())
+            .unwrap()
+    }
+
+    fn overlay_alpha(app: &mut App) -> f32 {
+        app.world_mut()
+            .query::<&ScreenOverlay>()
+            .single(app.world())
+            .unwrap()
+            .alpha()
+    }
+
+    #[test]
+    fn reduced_motion_stops_the_camera_moving() {
+        let mut app = effects_app(true);
+        start(&mut app, CameraEffect::Shake { trauma: 0.5 });
+        start(&mut app, CameraEffect::ZoomPunch { strength: 0.2 });
+        assert_eq!(shake(&mut app), CameraShake::default());
+
+        let mut app = effects_app(false);
+        start(&mut app, CameraEffect::Shake { trauma: 0.5 });
+        start(&mut app, CameraEffect::ZoomPunch { strength: 0.2 });
+        assert_eq!(
+            shake(&mut app),
+            CameraShake {
+                trauma: 0.5,
+                punch: 0.2
+            }
+        );
+    }
+
+    #[test]
+    fn reduced_motion_softens_flashes() {
+        let flash = CameraEffect::Flash {
+            color: Color::WHITE,
+            duration: 0.5,
+        };
+
+        let mut app = effects_app(true);
+        start(&mut app, flash);
+        assert_eq!(overlay_alpha(&mut app), REDUCED_FLASH_ALPHA);
+
+        let mut app = effects_app(false);
+        start(&mut app, flash);
+        assert_eq!(overlay_alpha(&mut app), 1.0);
+    }
+}
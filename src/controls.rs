@@ -1,10 +1,13 @@
 use crate::embed_asset;
+use crate::glyphs::GamepadGlyphs;
 use crate::prelude::*;
+use crate::replay::Replay;
 use bevy::ecs::hierarchy::ChildSpawnerCommands;
 use bevy::{input::InputSystem, prelude::*};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::iter::IntoIterator;
+use thiserror::Error;
 
 const KEYBINDS_DB_TABLE: &str = "Keybinds";
 
@@ -16,13 +19,24 @@ impl Plugin for ControlsPlugin {
 
         app.add_systems(Startup, setup_controls)
             .init_resource::<ControlState>()
+            .init_resource::<ActiveInputDevice>()
             .init_resource::<ButtonInput<Input>>()
-            .add_systems(
+            .configure_sets(
                 PreUpdate,
-                (update_input_state, update_control_state)
+                (ControlsSystem::UpdateInput, ControlsSystem::UpdateControls)
                     .chain()
                     .after(InputSystem),
             )
+            .add_systems(
+                PreUpdate,
+                (
+                    update_input_state
+                        .in_set(ControlsSystem::UpdateInput)
+                        .run_if(not(resource_exists::<Replay>)),
+                    (update_control_state, update_active_device)
+                        .in_set(ControlsSystem::UpdateControls),
+                ),
+            )
             .add_systems(
                 Update,
                 controls_sync
@@ -31,8 +45,24 @@ impl Plugin for ControlsPlugin {
     }
 }
 
-fn setup_controls(mut commands: Commands, database: Res<Database>) {
-    commands.insert_resource(Controls::from_database(&database));
+/// The systems that turn the device inputs into the [`ControlState`].
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ControlsSystem {
+    /// Updates the `ButtonInput<Input>` from the devices.
+    UpdateInput,
+    /// Updates the [`ControlState`] from the `ButtonInput<Input>`.
+    UpdateControls,
+}
+
+fn setup_controls(
+    mut commands: Commands,
+    database: Res<Database>,
+    controls: Option<Res<Controls>>,
+) {
+    // The controls may have already been provided, such as by a replay.
+    if controls.is_none() {
+        commands.insert_resource(Controls::from_database(&database));
+    }
 }
 
 #[derive(Clone, Default, Resource, Reflect)]
@@ -212,6 +242,21 @@ fn update_input_state(
     }
 }
 
+/// Switches the active device to the one that was last used.
+fn update_active_device(
+    mut active: ResMut<ActiveInputDevice>,
+    input_state: Res<ButtonInput<Input>>,
+) {
+    let Some(device) = input_state.get_just_pressed().last().map(Input::device) else {
+        return;
+    };
+
+    // Avoid triggering change detection when nothing changed.
+    if active.0 != device {
+        active.0 = device;
+    }
+}
+
 fn update_control_state(
     mut control_state: ResMut<ControlState>,
     input_state: Res<ButtonInput<Input>>,
@@ -243,25 +288,29 @@ fn update_control_state(
 pub struct Keybind(pub Control, pub InputList);
 
 impl Keybind {
-    pub fn to_screen(&self, _style: &Style, _builder: &mut ChildSpawnerCommands) {
-        todo!("display multiple");
+    /// Spawns Node(s) representing every input bound to the control.
+    pub fn to_screen(
+        &self,
+        style: &Style,
+        glyphs: &GamepadGlyphs,
+        builder: &mut ChildSpawnerCommands,
+    ) {
+        style.display_keybind(builder, glyphs, self);
     }
 }
 
-const TEXT_COLOR: Color = Color::srgb_u8(0xe0, 0xde, 0xf4);
-
-pub fn input_to_screen(style: &Style, builder: &mut ChildSpawnerCommands, input: &Option<Input>) {
+pub fn input_to_screen(
+    style: &Style,
+    glyphs: &GamepadGlyphs,
+    builder: &mut ChildSpawnerCommands,
+    input: &Option<Input>,
+) {
     match input {
-        Some(input) => style.display_input(builder, input),
+        Some(input) => style.display_input(builder, glyphs, input),
         None => {
             builder.spawn((
-                Text::new("Not Bound"),
-                TextFont {
-                    font: style.font.clone(),
-                    font_size: 33.0,
-                    ..default()
-                },
-                TextColor(TEXT_COLOR),
+                localized("controls.not_bound"),
+                style.text(StyleRole::Text, TextSize::Body),
                 Label,
                 Pickable::IGNORE,
             ));
@@ -272,7 +321,7 @@ pub fn input_to_screen(style: &Style, builder: &mut ChildSpawnerCommands, input:
 /// The number of keybinds associated with a given control.
 /// When changed, the update must be reflected in the database
 /// so that we sync all of them correctly.
-const INPUT_LIST_LEN: usize = 2;
+const INPUT_LIST_LEN: usize = 3;
 /// An individual set of inputs for a keybind
 pub type InputList = [Option<Input>; INPUT_LIST_LEN];
 
@@ -286,6 +335,33 @@ pub enum Input {
     GamepadAxis(GamepadAxis),
 }
 
+impl Input {
+    /// The kind of device this input comes from.
+    pub fn device(&self) -> InputDevice {
+        match self {
+            Input::Keyboard(_) | Input::Mouse(_) | Input::MouseWheelAxis(_) => {
+                InputDevice::KeyboardMouse
+            }
+            Input::Gamepad(_) | Input::GamepadAxis(_) => InputDevice::Gamepad,
+        }
+    }
+}
+
+/// The kinds of devices that the player can use.
+#[derive(Default, Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Default, Debug, Hash, PartialEq, Clone)]
+pub enum InputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad,
+}
+
+/// The device the player last pressed a button on,
+/// used to show them the inputs for the device they're holding.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default, Debug, Clone, PartialEq)]
+pub struct ActiveInputDevice(pub InputDevice);
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect, Serialize, Deserialize)]
 #[reflect(Debug, Hash, PartialEq, Clone, Serialize, Deserialize)]
 pub enum MouseWheelAxis {
@@ -581,6 +657,19 @@ impl Controls {
         }
     }
 
+    /// The input to show the player for a control, preferring inputs from `device`,
+    /// and resorting to any bound input otherwise.
+    pub fn prompt_input(&self, control: Control, device: InputDevice) -> Option<Input> {
+        let inputs = self.get_control(control);
+        let mut bound = inputs.iter().flatten();
+
+        bound
+            .clone()
+            .find(|input| input.device() == device)
+            .or_else(|| bound.next())
+            .copied()
+    }
+
     pub fn get_control_part(&self, control: Control, entry: usize) -> Option<Input> {
         assert!(entry < INPUT_LIST_LEN);
 
@@ -625,6 +714,59 @@ impl Controls {
         *self = default();
     }
 
+    /// Creates the controls from a list of keybinds, using the
+    /// defaults for any control not in the list.
+    pub fn from_keybinds(keybinds: impl IntoIterator<Item = Keybind>) -> Self {
+        let mut controls = Self::default();
+        for Keybind(control, inputs) in keybinds {
+            *controls.get_control_mut(control) = inputs;
+        }
+        controls
+    }
+
+    /// Finds the first place `input` is bound, skipping the entry `except`.
+    pub fn find_input(&self, input: Input, except: (Control, usize)) -> Option<(Control, usize)> {
+        self.clone()
+            .into_iter()
+            .flat_map(|Keybind(control, keys)| {
+                keys.into_iter()
+                    .enumerate()
+                    .filter_map(move |(i, key)| key.map(|key| (control, i, key)))
+            })
+            .find(|(control, i, key)| *key == input && (*control, *i) != except)
+            .map(|(control, i, _)| (control, i))
+    }
+
+    /// Returns every entry whose input is also bound to another entry.
+    pub fn conflicts(&self) -> HashSet<(Control, usize)> {
+        let mut bound: HashMap<Input, Vec<(Control, usize)>> = HashMap::new();
+
+        for Keybind(control, keys) in self.clone().into_iter() {
+            for (i, key) in keys.into_iter().enumerate() {
+                if let Some(key) = key {
+                    bound.entry(key).or_default().push((control, i));
+                }
+            }
+        }
+
+        bound
+            .into_values()
+            .filter(|entries| entries.len() > 1)
+            .flatten()
+            .collect()
+    }
+
+    /// Checks that the controls leave the player a way to navigate the menus.
+    pub fn validate(&self) -> Result<(), ControlsError> {
+        for control in REQUIRED_CONTROLS {
+            if self.get_control(control).iter().all(Option::is_none) {
+                return Err(ControlsError::Unbound(control));
+            }
+        }
+
+        Ok(())
+    }
+
     // TODO: Do this in a single transaction maybe? (don't know if it matters)
     fn from_database(db: &Database) -> Self {
         Self {
@@ -734,7 +876,20 @@ impl Iterator for ControlsIter {
     }
 }
 
-#[derive(Default, Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect, Serialize, Deserialize)]
+#[derive(
+    Default,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    Reflect,
+    Serialize,
+    Deserialize,
+)]
 #[reflect(Default, Debug, Hash, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Control {
     #[default]
@@ -762,6 +917,7 @@ impl Control {
         }
     }
 
+    /// The name of the control in English, for logs. The menus show its [`Localized`] name.
     pub fn as_string(self) -> &'static str {
         match self {
             Control::MoveUp => "Move Up",
@@ -776,6 +932,26 @@ impl Control {
     }
 }
 
+/// The controls that must always be bound, otherwise the
+/// player has no way to leave or interact with the menus.
+const REQUIRED_CONTROLS: [Control; 2] = [Control::Pause, Control::Select];
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlsError {
+    #[error("{0} must have at least one input bound!")]
+    Unbound(Control),
+}
+
+impl From<ControlsError> for Localized {
+    fn from(err: ControlsError) -> Self {
+        match err {
+            ControlsError::Unbound(control) => {
+                Localized::new("controls.error.unbound").with("control", control)
+            }
+        }
+    }
+}
+
 use std::fmt::{Display, Formatter};
 impl Display for Control {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
@@ -783,31 +959,62 @@ impl Display for Control {
     }
 }
 
+impl From<Control> for Localized {
+    fn from(control: Control) -> Self {
+        Localized::new(match control {
+            Control::MoveUp => "control.move_up",
+            Control::MoveDown => "control.move_down",
+            Control::MoveLeft => "control.move_left",
+            Control::MoveRight => "control.move_right",
+            Control::ZoomIn => "control.zoom_in",
+            Control::ZoomOut => "control.zoom_out",
+            Control::Pause => "control.pause",
+            Control::Select => "control.select",
+        })
+    }
+}
+
+// The last entry of each is the gamepad binding, make sure
+// to update the database migration when changing them.
 const DEFAULT_UP_CONTROLS: InputList = [
     Some(Input::Keyboard(KeyCode::ArrowUp)),
     Some(Input::Keyboard(KeyCode::KeyW)),
+    Some(Input::Gamepad(GamepadButton::DPadUp)),
 ];
 const DEFAULT_DOWN_CONTROLS: InputList = [
     Some(Input::Keyboard(KeyCode::ArrowDown)),
     Some(Input::Keyboard(KeyCode::KeyS)),
+    Some(Input::Gamepad(GamepadButton::DPadDown)),
 ];
 const DEFAULT_LEFT_CONTROLS: InputList = [
     Some(Input::Keyboard(KeyCode::ArrowLeft)),
     Some(Input::Keyboard(KeyCode::KeyA)),
+    Some(Input::Gamepad(GamepadButton::DPadLeft)),
 ];
 const DEFAULT_RIGHT_CONTROLS: InputList = [
     Some(Input::Keyboard(KeyCode::ArrowRight)),
     Some(Input::Keyboard(KeyCode::KeyD)),
+    Some(Input::Gamepad(GamepadButton::DPadRight)),
+];
+const DEFAULT_ZOOM_IN_CONTROLS: InputList = [
+    Some(Input::Keyboard(KeyCode::Comma)),
+    None,
+    Some(Input::Gamepad(GamepadButton::RightTrigger)),
+];
+const DEFAULT_ZOOM_OUT_CONTROLS: InputList = [
+    Some(Input::Keyboard(KeyCode::Period)),
+    None,
+    Some(Input::Gamepad(GamepadButton::LeftTrigger)),
 ];
-const DEFAULT_ZOOM_IN_CONTROLS: InputList = [Some(Input::Keyboard(KeyCode::Comma)), None];
-const DEFAULT_ZOOM_OUT_CONTROLS: InputList = [Some(Input::Keyboard(KeyCode::Period)), None];
 const DEFAULT_PAUSE_CONTROLS: InputList = [
     Some(Input::Keyboard(KeyCode::Escape)),
     Some(Input::Keyboard(KeyCode::CapsLock)),
+    Some(Input::Gamepad(GamepadButton::Start)),
 ];
 const DEFAULT_SELECT_CONTROLS: InputList = [
     Some(Input::Mouse(MouseButton::Left)),
     Some(Input::Keyboard(KeyCode::KeyE)),
+    Some(Input::Gamepad(GamepadButton::South)),
 ];
 
 fn controls_sync(database: Res<Database>, controls: Res<Controls>) {
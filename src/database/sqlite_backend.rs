@@ -17,7 +17,7 @@ pub type DatabaseError = sqlite::Error;
 
 type Version = i64;
 
-const DB_VERSION: Version = 5;
+const DB_VERSION: Version = 6;
 
 const ADD_SCHEMA: &str = formatcp!(
     r#"
@@ -137,6 +137,16 @@ impl Database {
         Ok(db)
     }
 
+    /// A new database that is gone once it is dropped, for tests.
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, OpenError> {
+        let db = Self {
+            connection: sqlite::Connection::open_thread_safe(":memory:")?,
+        };
+        db.connection.execute(ADD_SCHEMA)?;
+        Ok(db)
+    }
+
     pub fn get_kv_table_direct<T: sqlite::ReadableWithIndex>(
         &self,
         table: &str,
@@ -237,6 +247,14 @@ impl Database {
         }
     }
 
+    pub fn get_kv_or_default<T: Serialize + DeserializeOwned + Clone>(
+        &self,
+        key: &str,
+        default: T,
+    ) -> T {
+        self.get_kv_table_or_default("KeyValue", key, default)
+    }
+
     pub fn set_kv_table_direct<T: sqlite::BindableWithIndex>(
         &self,
         table: &str,
@@ -368,7 +386,7 @@ pub enum ValidateSchemaError {
     DatabaseError(#[from] DatabaseError),
 }
 
-const _: () = assert!(DB_VERSION == 5, "UPDATE VALIDATE SCRIPT");
+const _: () = assert!(DB_VERSION == 6, "UPDATE VALIDATE SCRIPT");
 fn validate_schema(db: &Database) -> Result<(), ValidateSchemaError> {
     let mut statement = db
         .connection
@@ -465,7 +483,7 @@ pub enum MigrationError {
 
 const MIN_VERSION_MIGRATEABLE: Version = 3;
 /// Make sure the migrations are set up properly
-const _: () = assert!(DB_VERSION == 5, "UPDATE THE MIGRATION SCRIPT");
+const _: () = assert!(DB_VERSION == 6, "UPDATE THE MIGRATION SCRIPT");
 
 /// MAINTENANCE: UPDATE EVERY DATABASE UPDGRADE
 fn migrate_database(db: &Database, from: Version) -> Result<(), MigrationError> {
@@ -483,6 +501,11 @@ fn migrate_database(db: &Database, from: Version) -> Result<(), MigrationError>
         from = 5;
     }
 
+    if from == 5 {
+        migrate_from_5_to_6(db)?;
+        from = 6;
+    }
+
     assert_eq!(
         from, DB_VERSION,
         "Failed to find migration script to migrate fully."
@@ -535,3 +558,27 @@ fn migrate_from_4_to_5(db: &Database) -> Result<(), DatabaseError> {
 
     Ok(())
 }
+
+/// Adds the third (gamepad) input to every keybind, using the default gamepad bindings.
+fn migrate_from_5_to_6(db: &Database) -> Result<(), DatabaseError> {
+    let query = r#"
+        BEGIN TRANSACTION;
+
+        UPDATE Version SET version = 6;
+
+        UPDATE Keybinds SET value = CONCAT(SUBSTR(value, 1, LENGTH(value) - 1), ',Some(Gamepad(DPadUp)))') WHERE key = 'move_up';
+        UPDATE Keybinds SET value = CONCAT(SUBSTR(value, 1, LENGTH(value) - 1), ',Some(Gamepad(DPadDown)))') WHERE key = 'move_down';
+        UPDATE Keybinds SET value = CONCAT(SUBSTR(value, 1, LENGTH(value) - 1), ',Some(Gamepad(DPadLeft)))') WHERE key = 'move_left';
+        UPDATE Keybinds SET value = CONCAT(SUBSTR(value, 1, LENGTH(value) - 1), ',Some(Gamepad(DPadRight)))') WHERE key = 'move_right';
+        UPDATE Keybinds SET value = CONCAT(SUBSTR(value, 1, LENGTH(value) - 1), ',Some(Gamepad(RightTrigger)))') WHERE key = 'zoom_in';
+        UPDATE Keybinds SET value = CONCAT(SUBSTR(value, 1, LENGTH(value) - 1), ',Some(Gamepad(LeftTrigger)))') WHERE key = 'zoom_out';
+        UPDATE Keybinds SET value = CONCAT(SUBSTR(value, 1, LENGTH(value) - 1), ',Some(Gamepad(Start)))') WHERE key = 'pause';
+        UPDATE Keybinds SET value = CONCAT(SUBSTR(value, 1, LENGTH(value) - 1), ',Some(Gamepad(South)))') WHERE key = 'select';
+
+        COMMIT;
+    "#;
+
+    db.connection.execute(query)?;
+
+    Ok(())
+}
@@ -12,6 +12,11 @@ impl Database {
         Ok(Self)
     }
 
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, DatabaseError> {
+        Ok(Self)
+    }
+
     pub fn get_kv_table_direct<T>(
         &self,
         _table: &str,
@@ -50,11 +55,15 @@ impl Database {
         default.into()
     }
 
+    pub fn get_kv_or_default<T, U: Into<T>>(&self, _key: &str, default: U) -> T {
+        default.into()
+    }
+
     pub fn set_kv_direct<T>(&self, _key: &str, _value: T) -> Result<(), DatabaseError> {
         Ok(())
     }
 
-    pub fn set_kv<T>(&self, _key: &str) -> Result<(), SetKvError> {
+    pub fn set_kv<T>(&self, _key: &str, _value: T) -> Result<(), SetKvError> {
         Ok(())
     }
 
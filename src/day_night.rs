@@ -0,0 +1,259 @@
+//! The time of day in the game, and the light it casts over the world.
+//!
+//! The [`GameClock`] only runs while the game does, stopping when it is paused, and is
+//! kept in the [`SavedGame`](crate::newgame::SavedGame). The [`Daylight`] follows it
+//! through dawn, day, dusk and night, tinting the sky and the rooms.
+use crate::newgame::{GameSession, RoomTile};
+use crate::prelude::*;
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How many seconds of play a whole day lasts.
+const DAY_LENGTH: f32 = 600.0;
+
+/// The times of day the [`Daylight`] is each [`DayPhase`]'s tint at, in order,
+/// and fades between.
+const DAYLIGHT_KEYS: [(f32, DayPhase); 8] = [
+    (0.0, DayPhase::Night),
+    (0.2, DayPhase::Night),
+    (0.25, DayPhase::Dawn),
+    (0.3, DayPhase::Day),
+    (0.7, DayPhase::Day),
+    (0.75, DayPhase::Dusk),
+    (0.8, DayPhase::Night),
+    (1.0, DayPhase::Night),
+];
+
+pub struct DayNightPlugin;
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GameClock>()
+            .register_type::<Daylight>()
+            .init_resource::<Daylight>()
+            .add_systems(OnEnter(GameState::Game), start_clock)
+            .add_systems(
+                OnExit(GameState::Game),
+                (remove_resource::<GameClock>, reset_daylight),
+            )
+            .add_systems(
+                Update,
+                (
+                    advance_clock
+                        .run_if(in_state(PauseState::Running).and(resource_exists::<GameClock>)),
+                    update_daylight.run_if(resource_exists_and_changed::<GameClock>),
+                    tint_rooms,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The time in the game being played, which only exists while in [`GameState::Game`].
+#[derive(Resource, Reflect, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct GameClock {
+    /// How far through the day it is, from `0` at midnight to just under `1` before the next.
+    pub time_of_day: f32,
+    /// How many days have gone by since the game started.
+    pub day: u32,
+}
+
+impl Default for GameClock {
+    /// Games start in the morning.
+    fn default() -> Self {
+        Self {
+            time_of_day: 0.3,
+            day: 0,
+        }
+    }
+}
+
+/// The parts of the day, which each light the world differently.
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DayPhase {
+    Dawn,
+    #[default]
+    Day,
+    Dusk,
+    Night,
+}
+
+impl DayPhase {
+    fn from_time_of_day(time_of_day: f32) -> Self {
+        if time_of_day < 0.2 {
+            DayPhase::Night
+        } else if time_of_day < 0.3 {
+            DayPhase::Dawn
+        } else if time_of_day < 0.7 {
+            DayPhase::Day
+        } else if time_of_day < 0.8 {
+            DayPhase::Dusk
+        } else {
+            DayPhase::Night
+        }
+    }
+
+    fn tint(self) -> Color {
+        match self {
+            DayPhase::Dawn => Color::srgb(1.0, 0.78, 0.65),
+            DayPhase::Day => Color::WHITE,
+            DayPhase::Dusk => Color::srgb(1.0, 0.6, 0.45),
+            DayPhase::Night => Color::srgb(0.35, 0.4, 0.65),
+        }
+    }
+}
+
+/// The light over the world at the time of the [`GameClock`], which
+/// is full daylight outside of the game.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource, Default, Debug, PartialEq)]
+pub struct Daylight {
+    pub phase: DayPhase,
+    /// The color everything is multiplied by.
+    pub tint: Color,
+}
+
+impl Default for Daylight {
+    fn default() -> Self {
+        Self {
+            phase: DayPhase::Day,
+            tint: Color::WHITE,
+        }
+    }
+}
+
+impl Daylight {
+    fn at(clock: &GameClock) -> Self {
+        let time = clock.time_of_day;
+        // The keys start at `0` and end at `1`, so the time is always between two of them.
+        let tint = DAYLIGHT_KEYS
+            .windows(2)
+            .find(|keys| time < keys[1].0)
+            .map(|keys| {
+                let ((from_time, from), (to_time, to)) = (keys[0], keys[1]);
+                let t = (time - from_time) / (to_time - from_time);
+                from.tint()
+                    .to_linear()
+                    .mix(&to.tint().to_linear(), t)
+                    .into()
+            })
+            .unwrap_or_else(|| DayPhase::Night.tint());
+
+        Self {
+            phase: DayPhase::from_time_of_day(time),
+            tint,
+        }
+    }
+
+    /// Lights `color` by the daylight.
+    pub fn light(&self, color: Color) -> Color {
+        let (color, tint) = (color.to_linear(), self.tint.to_linear());
+        LinearRgba::new(
+            color.red * tint.red,
+            color.green * tint.green,
+            color.blue * tint.blue,
+            color.alpha,
+        )
+        .into()
+    }
+}
+
+fn start_clock(mut commands: Commands, session: Res<GameSession>) {
+    commands.insert_resource(session.clock);
+}
+
+fn advance_clock(time: Res<Time>, mut clock: ResMut<GameClock>) {
+    clock.time_of_day += time.delta_secs() / DAY_LENGTH;
+    if clock.time_of_day >= 1.0 {
+        clock.time_of_day = clock.time_of_day.fract();
+        clock.day += 1;
+    }
+}
+
+fn update_daylight(clock: Res<GameClock>, mut daylight: ResMut<Daylight>) {
+    daylight.set_if_neq(Daylight::at(&clock));
+}
+
+/// Goes back to full daylight when leaving the game.
+fn reset_daylight(mut daylight: ResMut<Daylight>) {
+    daylight.set_if_neq(Daylight::default());
+}
+
+/// Colors the room tiles by the daylight, when it changes or there are new tiles.
+fn tint_rooms(
+    daylight: Res<Daylight>,
+    added: Query<(), Added<RoomTile>>,
+    mut tiles: Query<&mut TileColor, With<RoomTile>>,
+) {
+    if !daylight.is_changed() && added.is_empty() {
+        return;
+    }
+
+    for mut color in tiles.iter_mut() {
+        color.0 = daylight.tint;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use core::time::Duration;
+
+    fn daylight(time_of_day: f32) -> Daylight {
+        Daylight::at(&GameClock {
+            time_of_day,
+            day: 0,
+        })
+    }
+
+    /// Whether the colors are the same, but for rounding.
+    fn same_color(a: Color, b: Color) -> bool {
+        a.to_linear().to_vec4().distance(b.to_linear().to_vec4()) < 1e-5
+    }
+
+    #[test]
+    fn daylight_fades_between_the_keys() {
+        assert_eq!(daylight(0.1).phase, DayPhase::Night);
+        assert!(same_color(daylight(0.1).tint, DayPhase::Night.tint()));
+        assert_eq!(daylight(0.5).phase, DayPhase::Day);
+        assert!(same_color(daylight(0.5).tint, DayPhase::Day.tint()));
+
+        // On a key it is that key's tint.
+        let dawn = daylight(0.25);
+        assert_eq!(dawn.phase, DayPhase::Dawn);
+        assert!(same_color(dawn.tint, DayPhase::Dawn.tint()));
+
+        // Halfway between dawn and day it is halfway between their tints.
+        let halfway = DayPhase::Dawn
+            .tint()
+            .to_linear()
+            .mix(&DayPhase::Day.tint().to_linear(), 0.5);
+        assert!(same_color(daylight(0.275).tint, halfway.into()));
+
+        // Just before midnight it is night again.
+        assert_eq!(daylight(0.999).phase, DayPhase::Night);
+        assert!(same_color(daylight(0.999).tint, DayPhase::Night.tint()));
+    }
+
+    #[test]
+    fn the_clock_rolls_over_to_the_next_day() {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs_f32(DAY_LENGTH * 0.002));
+        world.insert_resource(time);
+        world.insert_resource(GameClock {
+            time_of_day: 0.999,
+            day: 2,
+        });
+
+        world.run_system_once(advance_clock).unwrap();
+
+        let clock = world.resource::<GameClock>();
+        assert_eq!(clock.day, 3);
+        assert!((clock.time_of_day - 0.001).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,201 @@
+//! The display settings, which are applied to the primary window.
+use crate::database::SetKvError;
+use crate::prelude::*;
+use bevy::{
+    prelude::*,
+    window::{MonitorSelection, PresentMode, PrimaryWindow, VideoModeSelection, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+const WINDOW_MODE_DB_KEY: &str = "window_mode";
+const RESOLUTION_DB_KEY: &str = "resolution";
+const SCALE_FACTOR_DB_KEY: &str = "scale_factor";
+const VSYNC_DB_KEY: &str = "vsync";
+const UI_SCALE_DB_KEY: &str = "ui_scale";
+const PIXEL_PERFECT_DB_KEY: &str = "pixel_perfect";
+const REDUCE_MOTION_DB_KEY: &str = "reduce_motion";
+
+/// The windowed resolutions the player can pick from.
+pub const RESOLUTIONS: [(u32, u32); 5] = [
+    (1280, 720),
+    (1366, 768),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1440),
+];
+/// The scale factors the player can pick from, `None` using the monitor's.
+pub const SCALE_FACTORS: [Option<f32>; 5] = [None, Some(1.0), Some(1.5), Some(2.0), Some(3.0)];
+pub const UI_SCALES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DisplaySettings>()
+            .add_systems(Startup, setup_display)
+            .add_systems(
+                Update,
+                (
+                    display_settings_sync.run_if(
+                        resource_changed::<DisplaySettings>
+                            .and(not(resource_added::<DisplaySettings>)),
+                    ),
+                    apply_display_settings.run_if(resource_changed::<DisplaySettings>),
+                ),
+            );
+    }
+}
+
+#[derive(Default, Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect, Serialize, Deserialize)]
+#[reflect(Default, Debug, Hash, PartialEq, Clone, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub const ALL: [Self; 3] = [Self::Windowed, Self::Borderless, Self::Fullscreen];
+
+    fn to_window_mode(self) -> WindowMode {
+        match self {
+            Self::Windowed => WindowMode::Windowed,
+            Self::Borderless => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+            Self::Fullscreen => {
+                WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+            }
+        }
+    }
+}
+
+impl From<DisplayMode> for Localized {
+    fn from(mode: DisplayMode) -> Self {
+        Localized::new(match mode {
+            DisplayMode::Windowed => "display_mode.windowed",
+            DisplayMode::Borderless => "display_mode.borderless",
+            DisplayMode::Fullscreen => "display_mode.fullscreen",
+        })
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Debug, Clone, PartialEq)]
+pub struct DisplaySettings {
+    pub mode: DisplayMode,
+    /// The logical size of the window when windowed.
+    pub resolution: (u32, u32),
+    /// Overrides the scale factor of the monitor.
+    pub scale_factor: Option<f32>,
+    pub vsync: bool,
+    pub ui_scale: f32,
+    /// Draw the world at a whole number of physical pixels for each of the art's
+    /// pixels, so every pixel of the art is drawn the same size.
+    /// See [`crate::pixel_perfect`].
+    pub pixel_perfect: bool,
+    /// Stops the camera from shaking and zooming for effects, and softens flashes.
+    /// See [`crate::camera_effects`].
+    pub reduce_motion: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            mode: DisplayMode::Windowed,
+            resolution: RESOLUTIONS[0],
+            scale_factor: None,
+            vsync: true,
+            ui_scale: 1.0,
+            pixel_perfect: false,
+            reduce_motion: false,
+        }
+    }
+}
+
+impl FromDatabase for DisplaySettings {
+    fn from_database(database: &Database) -> Self {
+        let default = Self::default();
+        Self {
+            mode: database.get_kv_or_default(WINDOW_MODE_DB_KEY, default.mode),
+            resolution: database.get_kv_or_default(RESOLUTION_DB_KEY, default.resolution),
+            scale_factor: database.get_kv_or_default(SCALE_FACTOR_DB_KEY, default.scale_factor),
+            vsync: database.get_kv_or_default(VSYNC_DB_KEY, default.vsync),
+            ui_scale: database.get_kv_or_default(UI_SCALE_DB_KEY, default.ui_scale),
+            pixel_perfect: database.get_kv_or_default(PIXEL_PERFECT_DB_KEY, default.pixel_perfect),
+            reduce_motion: database.get_kv_or_default(REDUCE_MOTION_DB_KEY, default.reduce_motion),
+        }
+    }
+}
+
+impl ToDatabase for DisplaySettings {
+    fn to_database(&self, database: &Database) -> Result<(), SetKvError> {
+        database.set_kv(WINDOW_MODE_DB_KEY, self.mode)?;
+        database.set_kv(RESOLUTION_DB_KEY, self.resolution)?;
+        database.set_kv(SCALE_FACTOR_DB_KEY, self.scale_factor)?;
+        database.set_kv(VSYNC_DB_KEY, self.vsync)?;
+        database.set_kv(UI_SCALE_DB_KEY, self.ui_scale)?;
+        database.set_kv(PIXEL_PERFECT_DB_KEY, self.pixel_perfect)?;
+        database.set_kv(REDUCE_MOTION_DB_KEY, self.reduce_motion)?;
+        Ok(())
+    }
+}
+
+impl DisplaySettings {
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+}
+
+fn setup_display(mut commands: Commands, database: Res<Database>) {
+    commands.insert_resource(DisplaySettings::from_database(&database));
+}
+
+fn display_settings_sync(database: Res<Database>, settings: Res<DisplaySettings>) {
+    if let Err(err) = settings.to_database(&database) {
+        warn!("Failed to sync display settings to database with: {err}");
+    }
+}
+
+/// Applies the settings that changed since they were last applied, so changing one
+/// setting doesn't undo the player resizing or moving the window.
+fn apply_display_settings(
+    settings: Res<DisplaySettings>,
+    mut applied: Local<Option<DisplaySettings>>,
+    mut ui_scale: ResMut<UiScale>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let last = applied.replace(*settings);
+    let mode_changed = last.is_none_or(|last| last.mode != settings.mode);
+    let resolution_changed = last.is_none_or(|last| last.resolution != settings.resolution);
+
+    if last.is_none_or(|last| last.ui_scale != settings.ui_scale) {
+        ui_scale.0 = settings.ui_scale;
+    }
+
+    // There is no window when running headless.
+    let Ok(mut window) = window.single_mut() else {
+        return;
+    };
+
+    if mode_changed {
+        window.mode = settings.mode.to_window_mode();
+    }
+    if last.is_none_or(|last| last.vsync != settings.vsync) {
+        window.present_mode = settings.present_mode();
+    }
+    if last.is_none_or(|last| last.scale_factor != settings.scale_factor) {
+        window
+            .resolution
+            .set_scale_factor_override(settings.scale_factor);
+    }
+
+    // Going back to windowed also restores the size.
+    if settings.mode == DisplayMode::Windowed && (mode_changed || resolution_changed) {
+        let (width, height) = settings.resolution;
+        window.resolution.set(width as f32, height as f32);
+    }
+}
@@ -0,0 +1,354 @@
+//! The button glyphs for gamepads, picked to match the connected gamepad.
+use crate::database::SetKvError;
+use crate::embed_asset;
+use crate::prelude::*;
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+const GLYPH_PREFERENCE_DB_KEY: &str = "glyph_preference";
+
+pub struct GlyphsPlugin;
+
+impl Plugin for GlyphsPlugin {
+    fn build(&self, app: &mut App) {
+        embed_asset!(app, "assets/glyphs/generic.png");
+        embed_asset!(app, "assets/glyphs/xbox.png");
+        embed_asset!(app, "assets/glyphs/playstation.png");
+        embed_asset!(app, "assets/glyphs/switch.png");
+        embed_asset!(app, "assets/glyphs/generic.glyphs.ron");
+        embed_asset!(app, "assets/glyphs/xbox.glyphs.ron");
+        embed_asset!(app, "assets/glyphs/playstation.glyphs.ron");
+        embed_asset!(app, "assets/glyphs/switch.glyphs.ron");
+
+        app.init_asset::<GlyphSet>()
+            .init_asset_loader::<GlyphSetLoader>()
+            .add_systems(Startup, setup_glyphs)
+            .add_systems(
+                Update,
+                (
+                    glyph_preference_sync.run_if(
+                        resource_changed::<GlyphPreference>
+                            .and(not(resource_added::<GlyphPreference>)),
+                    ),
+                    (
+                        update_glyph_sets.run_if(on_event::<AssetEvent<GlyphSet>>),
+                        select_glyph_set,
+                    )
+                        .chain()
+                        .run_if(resource_exists::<GamepadGlyphs>),
+                ),
+            );
+    }
+}
+
+fn setup_glyphs(mut commands: Commands, database: Res<Database>, asset_server: Res<AssetServer>) {
+    commands.insert_resource(GlyphPreference::from_database(&database));
+    commands.insert_resource(GamepadGlyphs::new(&asset_server));
+}
+
+fn glyph_preference_sync(database: Res<Database>, preference: Res<GlyphPreference>) {
+    if let Err(err) = preference.to_database(&database) {
+        warn!("Failed to sync glyph preference to database with: {err}");
+    }
+}
+
+/// The families of gamepads that have their own glyphs.
+#[derive(Default, Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect, Serialize, Deserialize)]
+#[reflect(Default, Debug, Hash, PartialEq, Clone, Serialize, Deserialize)]
+pub enum GlyphSetKind {
+    #[default]
+    Generic,
+    Xbox,
+    PlayStation,
+    Switch,
+}
+
+impl GlyphSetKind {
+    pub const ALL: [Self; 4] = [Self::Generic, Self::Xbox, Self::PlayStation, Self::Switch];
+
+    /// The name of the set's description, e.g. `xbox` for `assets/glyphs/xbox.glyphs.ron`.
+    fn file_name(self) -> &'static str {
+        match self {
+            Self::Generic => "generic",
+            Self::Xbox => "xbox",
+            Self::PlayStation => "playstation",
+            Self::Switch => "switch",
+        }
+    }
+}
+
+/// Which glyphs the user wants to see.
+#[derive(Resource, Default, Debug, PartialEq, Eq, Clone, Copy, Reflect, Serialize, Deserialize)]
+#[reflect(Resource, Default, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum GlyphPreference {
+    /// Use the glyphs of the last connected gamepad.
+    #[default]
+    Auto,
+    Fixed(GlyphSetKind),
+}
+
+impl GlyphPreference {
+    /// The preference after this one, used to cycle through them in the menu.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Auto => Self::Fixed(GlyphSetKind::Generic),
+            Self::Fixed(GlyphSetKind::Generic) => Self::Fixed(GlyphSetKind::Xbox),
+            Self::Fixed(GlyphSetKind::Xbox) => Self::Fixed(GlyphSetKind::PlayStation),
+            Self::Fixed(GlyphSetKind::PlayStation) => Self::Fixed(GlyphSetKind::Switch),
+            Self::Fixed(GlyphSetKind::Switch) => Self::Auto,
+        }
+    }
+}
+
+impl From<GlyphPreference> for Localized {
+    fn from(preference: GlyphPreference) -> Self {
+        match preference {
+            GlyphPreference::Auto => Localized::new("glyphs.auto"),
+            GlyphPreference::Fixed(GlyphSetKind::Generic) => Localized::new("glyphs.generic"),
+            // The names of the consoles are the same in every language.
+            GlyphPreference::Fixed(GlyphSetKind::Xbox) => Localized::verbatim("Xbox"),
+            GlyphPreference::Fixed(GlyphSetKind::PlayStation) => Localized::verbatim("PlayStation"),
+            GlyphPreference::Fixed(GlyphSetKind::Switch) => Localized::verbatim("Switch"),
+        }
+    }
+}
+
+impl FromDatabase for GlyphPreference {
+    fn from_database(database: &Database) -> Self {
+        database.get_kv_or_default(GLYPH_PREFERENCE_DB_KEY, Self::default())
+    }
+}
+
+impl ToDatabase for GlyphPreference {
+    fn to_database(&self, database: &Database) -> Result<(), SetKvError> {
+        database.set_kv(GLYPH_PREFERENCE_DB_KEY, self)
+    }
+}
+
+/// The contents of a glyph set's `.glyphs.ron` description file.
+#[derive(Deserialize, Debug)]
+struct GlyphAtlasDescription {
+    name: String,
+    image: String,
+    tile_size: (u32, u32),
+    columns: u32,
+    rows: u32,
+    /// The USB vendor ids of gamepads that use this set.
+    vendors: Vec<u16>,
+    /// The USB (vendor, product) ids of gamepads that use this set,
+    /// for when a vendor makes gamepads for multiple families.
+    products: Vec<(u16, u16)>,
+    buttons: HashMap<GamepadButton, GlyphEntry>,
+}
+
+/// A single glyph in a glyph set.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GlyphEntry {
+    /// The index of the glyph in the atlas.
+    pub index: usize,
+    /// Text to draw on top of the glyph, if any.
+    pub label: Option<String>,
+}
+
+/// A loaded glyph set.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct GlyphSet {
+    pub name: String,
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub size: UVec2,
+    vendors: Vec<u16>,
+    products: Vec<(u16, u16)>,
+    buttons: HashMap<GamepadButton, GlyphEntry>,
+}
+
+impl GlyphSet {
+    fn matches(&self, vendor: Option<u16>, product: Option<u16>) -> bool {
+        match (vendor, product) {
+            (Some(vendor), Some(product)) if self.products.contains(&(vendor, product)) => true,
+            (Some(vendor), _) => self.vendors.contains(&vendor),
+            (None, _) => false,
+        }
+    }
+
+    pub fn glyph(&self, button: GamepadButton) -> Option<&GlyphEntry> {
+        self.buttons.get(&button)
+    }
+
+    pub fn to_node(&self, entry: &GlyphEntry) -> ImageNode {
+        ImageNode {
+            image: self.image.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: self.layout.clone(),
+                index: entry.index,
+            }),
+            ..default()
+        }
+    }
+
+    pub fn to_sprite(&self, entry: &GlyphEntry) -> Sprite {
+        Sprite::from_atlas_image(
+            self.image.clone(),
+            TextureAtlas {
+                layout: self.layout.clone(),
+                index: entry.index,
+            },
+        )
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum GlyphSetError {
+    #[error("Failed to read the glyph set with: {0}")]
+    Read(#[from] std::io::Error),
+    #[error("Failed to parse the glyph set with: {0}")]
+    Parse(#[from] ron::de::SpannedError),
+}
+
+#[derive(Default)]
+struct GlyphSetLoader;
+
+impl AssetLoader for GlyphSetLoader {
+    type Asset = GlyphSet;
+    type Settings = ();
+    type Error = GlyphSetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let description: GlyphAtlasDescription = ron::de::from_bytes(&bytes)?;
+
+        let size = UVec2::from(description.tile_size);
+        let layout =
+            TextureAtlasLayout::from_grid(size, description.columns, description.rows, None, None);
+
+        Ok(GlyphSet {
+            name: description.name,
+            image: load_context.load(description.image),
+            layout: load_context.add_labeled_asset("layout".into(), layout),
+            size,
+            vendors: description.vendors,
+            products: description.products,
+            buttons: description.buttons,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["glyphs.ron"]
+    }
+}
+
+/// All of the glyph sets, and which is being shown.
+#[derive(Resource)]
+pub struct GamepadGlyphs {
+    handles: HashMap<GlyphSetKind, Handle<GlyphSet>>,
+    /// The glyph sets that have loaded.
+    sets: HashMap<GlyphSetKind, GlyphSet>,
+    /// The glyph set matching the last connected gamepad.
+    detected: GlyphSetKind,
+    pub active: GlyphSetKind,
+}
+
+impl GamepadGlyphs {
+    pub fn new(asset_server: &AssetServer) -> Self {
+        Self {
+            handles: GlyphSetKind::ALL
+                .into_iter()
+                .map(|kind| {
+                    let path = format!("embedded://assets/glyphs/{}.glyphs.ron", kind.file_name());
+                    (kind, asset_server.load(path))
+                })
+                .collect(),
+            sets: HashMap::new(),
+            detected: GlyphSetKind::Generic,
+            active: GlyphSetKind::Generic,
+        }
+    }
+
+    /// The glyph set currently in use, if it loaded.
+    pub fn active_set(&self) -> Option<&GlyphSet> {
+        self.sets.get(&self.active)
+    }
+
+    /// Finds the glyph set for a gamepad, resorting to the generic set.
+    fn detect(&self, gamepad: &Gamepad) -> GlyphSetKind {
+        GlyphSetKind::ALL
+            .into_iter()
+            .find(|kind| {
+                self.sets
+                    .get(kind)
+                    .is_some_and(|set| set.matches(gamepad.vendor_id(), gamepad.product_id()))
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Copies the glyph sets that loaded, so they can be shown without looking them up.
+fn update_glyph_sets(mut glyphs: ResMut<GamepadGlyphs>, assets: Res<Assets<GlyphSet>>) {
+    let GamepadGlyphs { handles, sets, .. } = &mut *glyphs;
+    for (kind, handle) in handles.iter() {
+        if let Some(set) = assets.get(handle) {
+            sets.insert(*kind, set.clone());
+        }
+    }
+}
+
+/// Picks the glyph set from the preference, or the most recently connected gamepad.
+fn select_glyph_set(
+    mut glyphs: ResMut<GamepadGlyphs>,
+    preference: Res<GlyphPreference>,
+    added: Query<&Gamepad, Added<Gamepad>>,
+) {
+    if let Some(gamepad) = added.iter().last() {
+        let detected = glyphs.detect(gamepad);
+        if detected != glyphs.detected {
+            info!("Detected gamepad glyphs {detected:?}");
+            glyphs.detected = detected;
+        }
+    }
+
+    let active = match *preference {
+        GlyphPreference::Auto => glyphs.detected,
+        GlyphPreference::Fixed(kind) => kind,
+    };
+
+    // Avoid triggering change detection when nothing changed.
+    if glyphs.active != active {
+        glyphs.active = active;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless_app;
+    use core::time::Duration;
+
+    #[test]
+    fn every_glyph_set_loads() {
+        let mut app = headless_app();
+        for _ in 0..500 {
+            app.update();
+            if app.world().resource::<GamepadGlyphs>().sets.len() == GlyphSetKind::ALL.len() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let glyphs = app.world().resource::<GamepadGlyphs>();
+        for kind in GlyphSetKind::ALL {
+            let set = glyphs.sets.get(&kind).expect("the glyph set loaded");
+            assert!(set.glyph(GamepadButton::South).is_some());
+        }
+    }
+}
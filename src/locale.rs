@@ -0,0 +1,499 @@
+//! Translations of the text in the menus, looked up by key in the player's [`Language`].
+//!
+//! Each language has a message catalog in `assets/locales`, mapping keys to messages
+//! such as `"controls.reset_control": "Reset {control}"`. Text is spawned with a
+//! [`Localized`] rather than a string, see [`localized`], and is rewritten whenever
+//! the language changes. Messages missing from a catalog fall back to English,
+//! and then to the key itself.
+use crate::controls::Input;
+use crate::database::SetKvError;
+use crate::embed_asset;
+use crate::prelude::*;
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+const LANGUAGE_DB_KEY: &str = "language";
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        embed_asset!(app, "assets/locales/en.lang.ron");
+        embed_asset!(app, "assets/locales/es.lang.ron");
+
+        app.init_asset::<Catalog>()
+            .init_asset_loader::<CatalogLoader>()
+            .init_resource::<Messages>()
+            .add_systems(Startup, setup_locale)
+            .add_systems(
+                Update,
+                (
+                    language_sync
+                        .run_if(resource_changed::<Language>.and(not(resource_added::<Language>))),
+                    update_messages
+                        .run_if(resource_changed::<Language>.or(on_event::<AssetEvent<Catalog>>)),
+                    localize_texts,
+                )
+                    .chain(),
+            );
+
+        #[cfg(debug_assertions)]
+        app.add_systems(
+            Update,
+            (
+                validate_catalogs.run_if(on_event::<AssetEvent<Catalog>>),
+                validate_keys,
+            )
+                .after(localize_texts),
+        );
+    }
+}
+
+fn setup_locale(mut commands: Commands, database: Res<Database>, asset_server: Res<AssetServer>) {
+    commands.insert_resource(Language::from_database(&database));
+    commands.insert_resource(Catalogs::new(&asset_server));
+}
+
+fn language_sync(database: Res<Database>, language: Res<Language>) {
+    if let Err(err) = language.to_database(&database) {
+        warn!("Failed to sync language to database with: {err}");
+    }
+}
+
+/// The languages the menus can be shown in.
+#[derive(
+    Resource, Default, Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect, Serialize, Deserialize,
+)]
+#[reflect(
+    Resource,
+    Default,
+    Debug,
+    Hash,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::Spanish];
+
+    /// The code naming the language's catalog, e.g. `en` for `assets/locales/en.lang.ron`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+        }
+    }
+
+    /// The name of the language in itself, so the player can find theirs from any other.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Spanish => "Español",
+        }
+    }
+}
+
+impl FromDatabase for Language {
+    fn from_database(database: &Database) -> Self {
+        database.get_kv_or_default(LANGUAGE_DB_KEY, Self::default())
+    }
+}
+
+impl ToDatabase for Language {
+    fn to_database(&self, database: &Database) -> Result<(), SetKvError> {
+        database.set_kv(LANGUAGE_DB_KEY, self)
+    }
+}
+
+/// The messages of a language by their key, read from a `.lang.ron` file.
+#[derive(Asset, TypePath, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Catalog(pub HashMap<String, String>);
+
+#[derive(Error, Debug)]
+pub enum CatalogError {
+    #[error("Failed to read the catalog with: {0}")]
+    Read(#[from] std::io::Error),
+    #[error("Failed to parse the catalog with: {0}")]
+    Parse(#[from] ron::de::SpannedError),
+}
+
+#[derive(Default)]
+struct CatalogLoader;
+
+impl AssetLoader for CatalogLoader {
+    type Asset = Catalog;
+    type Settings = ();
+    type Error = CatalogError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["lang.ron"]
+    }
+}
+
+/// The catalog of every language, all loaded up front so that switching is instant.
+#[derive(Resource, Debug)]
+pub struct Catalogs(HashMap<Language, Handle<Catalog>>);
+
+impl Catalogs {
+    fn new(asset_server: &AssetServer) -> Self {
+        Self(
+            Language::ALL
+                .into_iter()
+                .map(|language| {
+                    let path = format!("embedded://assets/locales/{}.lang.ron", language.code());
+                    (language, asset_server.load(path))
+                })
+                .collect(),
+        )
+    }
+
+    /// Whether every catalog has either loaded or failed to, so the menus can be shown.
+    pub fn settled(&self, asset_server: &AssetServer) -> bool {
+        self.0.values().all(|handle| {
+            let state = asset_server.load_state(handle.id());
+            state.is_loaded() || state.is_failed()
+        })
+    }
+
+    fn get<'a>(&self, language: Language, assets: &'a Assets<Catalog>) -> Option<&'a Catalog> {
+        self.0.get(&language).and_then(|handle| assets.get(handle))
+    }
+}
+
+/// The messages of the player's [`Language`], with English filling in any it is missing.
+#[derive(Resource, Debug, Default)]
+pub struct Messages(HashMap<String, String>);
+
+impl Messages {
+    /// The message for `key`, or the key itself if no catalog has it.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.0.get(key).map(String::as_str).unwrap_or(key)
+    }
+
+    /// Writes out `text` in the player's language.
+    pub fn format(&self, text: &Localized) -> String {
+        match text {
+            Localized::Message { key, args } => args
+                .iter()
+                .fold(self.get(key).to_string(), |message, (name, arg)| {
+                    message.replace(&format!("{{{name}}}"), &self.format(arg))
+                }),
+            Localized::Verbatim(text) => text.clone(),
+            // Most inputs are named the same in every language, so only some are translated.
+            Localized::Input(input) => self
+                .0
+                .get(&input_key(input))
+                .cloned()
+                .unwrap_or_else(|| input.to_string()),
+        }
+    }
+}
+
+/// The key of the message naming `input`, e.g. `input.Keyboard(Space)`.
+fn input_key(input: &Input) -> String {
+    format!("input.{input:?}")
+}
+
+/// Text shown in the player's language.
+///
+/// Put this on a [`Text`] to have it written out, and rewritten when the language changes.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub enum Localized {
+    /// The message with the key, with each `{name}` in it replaced by its argument.
+    Message {
+        key: String,
+        args: Vec<(&'static str, Localized)>,
+    },
+    /// Text that is the same in every language, such as numbers and file paths.
+    Verbatim(String),
+    /// The name of an input.
+    Input(Input),
+}
+
+impl Localized {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self::Message {
+            key: key.into(),
+            args: vec![],
+        }
+    }
+
+    pub fn verbatim(text: impl Into<String>) -> Self {
+        Self::Verbatim(text.into())
+    }
+
+    /// Replaces `{name}` in the message with `arg`.
+    pub fn with(mut self, name: &'static str, arg: impl Into<Localized>) -> Self {
+        if let Self::Message { args, .. } = &mut self {
+            args.push((name, arg.into()));
+        }
+        self
+    }
+
+    /// Whether this is shown as nothing in every language.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Verbatim(text) if text.is_empty())
+    }
+
+    /// Calls `visit` with the key of this message and of each of its arguments.
+    #[cfg(debug_assertions)]
+    fn visit_keys(&self, visit: &mut impl FnMut(&str)) {
+        if let Self::Message { key, args } = self {
+            visit(key);
+            for (_, arg) in args {
+                arg.visit_keys(visit);
+            }
+        }
+    }
+}
+
+impl Default for Localized {
+    fn default() -> Self {
+        Self::verbatim("")
+    }
+}
+
+impl From<&str> for Localized {
+    fn from(key: &str) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<Input> for Localized {
+    fn from(input: Input) -> Self {
+        Self::Input(input)
+    }
+}
+
+/// A UI text node showing `text` in the player's language.
+pub fn localized(text: impl Into<Localized>) -> (Text, Localized) {
+    (Text::default(), text.into())
+}
+
+/// Merges the catalog of the player's language over the English one.
+fn update_messages(
+    language: Res<Language>,
+    catalogs: Res<Catalogs>,
+    assets: Res<Assets<Catalog>>,
+    mut messages: ResMut<Messages>,
+) {
+    let mut merged = catalogs
+        .get(Language::English, &assets)
+        .map(|catalog| catalog.0.clone())
+        .unwrap_or_default();
+
+    if *language != Language::English {
+        if let Some(catalog) = catalogs.get(*language, &assets) {
+            merged.extend(catalog.0.clone());
+        }
+    }
+
+    messages.0 = merged;
+}
+
+/// Writes out the text of nodes whose [`Localized`] or [`Messages`] changed.
+pub fn localize_texts(messages: Res<Messages>, mut texts: Query<(Ref<Localized>, &mut Text)>) {
+    for (localized, mut text) in texts.iter_mut() {
+        if !messages.is_changed() && !localized.is_changed() {
+            continue;
+        }
+
+        let formatted = messages.format(&localized);
+        if text.0 != formatted {
+            text.0 = formatted;
+        }
+    }
+}
+
+/// Checks that every catalog has the same keys as English, once they have all loaded.
+#[cfg(debug_assertions)]
+fn validate_catalogs(catalogs: Res<Catalogs>, assets: Res<Assets<Catalog>>) {
+    let Some(english) = catalogs.get(Language::English, &assets) else {
+        return;
+    };
+
+    let loaded = Language::ALL
+        .into_iter()
+        .filter_map(|language| Some((language, catalogs.get(language, &assets)?)))
+        .collect::<Vec<_>>();
+    if loaded.len() != Language::ALL.len() {
+        return;
+    }
+
+    let difference = |a: &Catalog, b: &Catalog| {
+        let mut keys =
+            a.0.keys()
+                .filter(|key| !b.0.contains_key(*key))
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys.join(", ")
+    };
+
+    for (language, catalog) in loaded {
+        let missing = difference(english, catalog);
+        if !missing.is_empty() {
+            error!("The {language:?} catalog is missing the keys: {missing}");
+        }
+
+        let unknown = difference(catalog, english);
+        if !unknown.is_empty() {
+            error!("The {language:?} catalog has keys that English doesn't: {unknown}");
+        }
+    }
+}
+
+/// Checks that the text being shown only uses keys that are in the catalogs.
+#[cfg(debug_assertions)]
+fn validate_keys(
+    messages: Res<Messages>,
+    texts: Query<Ref<Localized>>,
+    mut reported: Local<bevy::platform::collections::HashSet<String>>,
+) {
+    if messages.0.is_empty() {
+        return;
+    }
+
+    for text in texts.iter() {
+        if !messages.is_changed() && !text.is_changed() {
+            continue;
+        }
+
+        text.visit_keys(&mut |key| {
+            if !messages.0.contains_key(key) && reported.insert(key.to_string()) {
+                error!("No catalog has a message for the key {key:?}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::path::Path;
+
+    fn keys(path: &Path) -> BTreeSet<String> {
+        let text = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("Failed to read {} with: {err}", path.display()));
+        let catalog: Catalog = ron::from_str(&text)
+            .unwrap_or_else(|err| panic!("Failed to parse {} with: {err}", path.display()));
+        catalog.0.into_keys().collect()
+    }
+
+    #[test]
+    fn catalogs_have_the_english_keys() {
+        let locales = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/locales");
+        let english = keys(&locales.join("en.lang.ron"));
+
+        let mut catalogs = 0;
+        for entry in std::fs::read_dir(&locales).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.to_string_lossy().ends_with(".lang.ron") {
+                continue;
+            }
+            catalogs += 1;
+
+            let catalog = keys(&path);
+            let missing = english.difference(&catalog).collect::<Vec<_>>();
+            let unknown = catalog.difference(&english).collect::<Vec<_>>();
+            assert!(
+                missing.is_empty() && unknown.is_empty(),
+                "{} is missing {missing:?} and has keys English doesn't: {unknown:?}",
+                path.display()
+            );
+        }
+
+        assert_eq!(catalogs, Language::ALL.len());
+    }
+
+    /// The `.rs` files in `dir` and its subdirectories.
+    fn sources(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                sources(&path, files);
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                files.push(path);
+            }
+        }
+    }
+
+    /// Whether `text` looks like a message key, such as `menu.back`,
+    /// rather than the name of a file, such as `glyphs.ron`.
+    fn is_key(text: &str) -> bool {
+        text.contains('.')
+            && !text.starts_with('.')
+            && !text.ends_with('.')
+            && !text.ends_with(".ron")
+            && text.starts_with(|c: char| c.is_ascii_lowercase())
+            && text
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.')
+    }
+
+    /// Every string in the code that looks like a key of one of the groups of keys
+    /// in English, such as `menu.` or `display.`, has to be in English.
+    ///
+    /// Keys built at runtime, such as with `format!`, aren't checked.
+    #[test]
+    fn used_keys_are_in_the_english_catalog() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let english = keys(&root.join("assets/locales/en.lang.ron"));
+        let groups: BTreeSet<&str> = english
+            .iter()
+            .filter_map(|key| key.split_once('.').map(|(group, _)| group))
+            .collect();
+
+        let mut files = Vec::new();
+        sources(&root.join("src"), &mut files);
+
+        let mut used = 0;
+        let mut missing = BTreeSet::new();
+        for path in files {
+            let code = std::fs::read_to_string(&path).unwrap();
+            // The pieces between the quotes are the strings and the code between
+            // them, which never looks like a key.
+            for key in code.split('"').filter(|text| is_key(text)) {
+                let group = key.split_once('.').map(|(group, _)| group).unwrap_or(key);
+                if !groups.contains(group) {
+                    continue;
+                }
+                used += 1;
+                if !english.contains(key) {
+                    missing.insert(format!("{key} in {}", path.display()));
+                }
+            }
+        }
+
+        assert!(used > 0, "No keys were found in the code");
+        assert!(
+            missing.is_empty(),
+            "The English catalog is missing {missing:?}"
+        );
+    }
+}
@@ -1,16 +1,29 @@
 mod camera;
+mod camera_effects;
 mod consts;
 mod controls;
 mod database;
+mod day_night;
+mod display;
+mod glyphs;
+mod locale;
 mod menu;
 mod newgame;
+mod noise;
+mod pixel_perfect;
+mod prompt;
+mod replay;
+mod settings;
 mod sky;
+mod sound;
 mod style;
+mod theme;
+mod typography;
 mod util;
 //mod tiles;
 
 pub mod prelude {
-    use bevy::prelude::States;
+    use bevy::prelude::{StateSet, States, SubStates};
     pub type RandomSource = wyrand::WyRand;
 
     #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
@@ -21,22 +34,45 @@ pub mod prelude {
         Game,
     }
 
+    /// Whether the game is paused, which freezes game time
+    /// while keeping the world around.
+    #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, SubStates)]
+    #[source(GameState = GameState::Game)]
+    pub enum PauseState {
+        #[default]
+        Running,
+        Paused,
+    }
+
     pub use crate::consts::*;
 
     pub use crate::controls::{Control, ControlState, Controls, Keybind};
     pub use crate::database::{Database, DatabaseError, FromDatabase, ToDatabase};
-    pub use crate::style::{Icons, Style};
+    pub use crate::locale::{Localized, Messages, localized};
+    pub use crate::replay::WorldSeed;
+    pub use crate::style::{Icons, Style, StyleRole};
+    pub use crate::typography::TextSize;
     pub use crate::util::*;
 }
 
 use camera::CameraPlugin;
+use camera_effects::CameraEffectsPlugin;
 use controls::ControlsPlugin;
 use database::DatabasePlugin;
+use day_night::DayNightPlugin;
+use display::DisplayPlugin;
+use glyphs::GlyphsPlugin;
+use locale::{Catalogs, LocalePlugin};
 use menu::MenuPlugin;
 use newgame::NewGamePlugin;
+use pixel_perfect::PixelPerfectPlugin;
 use prelude::*;
+use prompt::PromptPlugin;
+use replay::{ArgsError, ReplayPlugin};
 use sky::SkyPlugin;
+use sound::SoundPlugin;
 use style::StylePlugin;
+use typography::TypographyPlugin;
 
 #[cfg(feature = "debug")]
 use bevy::{
@@ -44,27 +80,59 @@ use bevy::{
     text::FontSmoothing,
 };
 
-use bevy::prelude::*;
+use bevy::{
+    app::ScheduleRunnerPlugin,
+    log::LogPlugin,
+    prelude::*,
+    render::{RenderPlugin, settings::WgpuSettings},
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
 use bevy_ecs_tilemap::{/*FrustumCulling, helpers::hex_grid::axial::AxialPos, */ prelude::*};
+use core::time::Duration;
 
-fn main() {
-    let mut app = App::new();
+fn main() -> AppExit {
+    let replay = match ReplayPlugin::from_args() {
+        Ok(replay) => replay,
+        Err(err) => return report_args_error(err),
+    };
 
-    app.add_plugins(
-        DefaultPlugins
-            .set(ImagePlugin::default_nearest())
-            .set(WindowPlugin {
+    let mut plugins = DefaultPlugins
+        .set(ImagePlugin::default_nearest()) // fallback to nearest sampling
+        .set(if replay.headless {
+            WindowPlugin {
+                primary_window: None,
+                // The replay exits once it is done.
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            }
+        } else {
+            WindowPlugin {
                 primary_window: Some(Window {
                     title: "RaMmYen Game".into(),
                     fit_canvas_to_parent: true,
                     ..default()
                 }),
                 ..default()
-            }),
-    ); // fallback to nearest sampling
+            }
+        });
+    if replay.headless {
+        // Without a display or a GPU, such as in CI.
+        plugins = plugins
+            .disable::<WinitPlugin>()
+            .add(ScheduleRunnerPlugin::run_loop(Duration::ZERO))
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            });
+    }
 
-    // Embed the sprite assets.
-    embed_asset!(app, "assets/sprites/basic_sheet.png");
+    let mut app = App::new();
+    app.add_plugins(plugins);
 
     #[cfg(feature = "debug")]
     app.add_plugins(FpsOverlayPlugin {
@@ -81,33 +149,99 @@ fn main() {
         },
     });
 
+    // The replay provides the world seed, so it has to be first.
+    app.add_plugins(replay);
+    app.add_plugins(DatabasePlugin);
+    add_game(&mut app);
+    app.run()
+}
+
+/// Reports `err` once there is a logger, without starting the game.
+///
+/// The arguments are read before there is a logger to report them to.
+fn report_args_error(err: ArgsError) -> AppExit {
+    App::new()
+        .add_plugins((MinimalPlugins, LogPlugin::default()))
+        .add_systems(Startup, move |mut exit: EventWriter<AppExit>| {
+            error!("Failed to read the arguments with: {err}");
+            exit.write(AppExit::error());
+        })
+        .run()
+}
+
+/// Adds everything of the game itself, once there is a [`ReplayPlugin`] and a [`Database`].
+fn add_game(app: &mut App) {
+    // Embed the sprite assets.
+    embed_asset!(app, "assets/sprites/basic_sheet.png");
+
     // foreign plugins
     app.add_plugins(TilemapPlugin);
     // State
     app.init_state::<GameState>();
+    app.add_sub_state::<PauseState>();
     // Local Plugins
-    app.add_plugins(DatabasePlugin);
-
-    app.add_plugins(StylePlugin)
+    app.add_plugins(LocalePlugin)
+        .add_plugins(StylePlugin)
+        .add_plugins(TypographyPlugin)
         .add_plugins(ControlsPlugin)
+        .add_plugins(GlyphsPlugin)
+        .add_plugins(PromptPlugin)
+        .add_plugins(SoundPlugin)
+        .add_plugins(DisplayPlugin)
         .add_plugins(MenuPlugin)
+        .add_plugins(DayNightPlugin)
         .add_plugins(SkyPlugin)
         //.add_plugins(SavePlugin)
         .add_plugins(CameraPlugin)
+        .add_plugins(CameraEffectsPlugin)
+        .add_plugins(PixelPerfectPlugin)
         //.insert_resource::<GlobalRandom>(GlobalRandom(rand))
         //.add_systems(Startup, spawn_floors)
         .add_plugins(NewGamePlugin);
     app.add_systems(
         Update,
         check_textures.run_if(in_state(GameState::InitialLoading)),
+    );
+}
+
+/// The game without a window or a GPU, for the tests.
+#[cfg(test)]
+fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .build()
+            .disable::<WinitPlugin>()
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            })
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            }),
     )
-    .run();
+    .add_plugins(ReplayPlugin::default())
+    .insert_resource(Database::open_in_memory().unwrap());
+    add_game(&mut app);
+    app
 }
 
 // Wait for everything to load
-// TODO: Find a better way to wait, because this just transitions immediately.
-fn check_textures(mut next_state: ResMut<NextState<GameState>>) {
-    next_state.set(GameState::Menu);
+// TODO: Wait for the textures too, this only waits for the message catalogs.
+fn check_textures(
+    mut next_state: ResMut<NextState<GameState>>,
+    asset_server: Res<AssetServer>,
+    catalogs: Res<Catalogs>,
+) {
+    if catalogs.settled(&asset_server) {
+        next_state.set(GameState::Menu);
+    }
 }
 
 //const AXIAL_DIRECTIONS: [AxialPos; 7] = [
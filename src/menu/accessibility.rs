@@ -0,0 +1,513 @@
+//! Describes the menus to screen readers through the accessibility tree.
+//!
+//! Bevy already publishes buttons named by their text, but knows nothing of the
+//! menu widgets, so these are given their roles, names and values here.
+//! This runs in [`Last`] so that it comes after Bevy's own descriptions,
+//! which are redone whenever a [`Button`] changes.
+use super::*;
+use crate::prelude::*;
+
+use bevy::{
+    a11y::{AccessibilityNode, ActionRequest},
+    input_focus::InputFocus,
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use accesskit::{Action, ActionData, Toggled};
+
+pub struct MenuAccessibilityPlugin;
+
+impl Plugin for MenuAccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        // Sent by the windowing backend, which isn't there when headless.
+        app.add_event::<ActionRequest>()
+            .init_resource::<InputFocus>()
+            .add_systems(
+                Update,
+                (
+                    accessibility_actions.run_if(not(in_state(MenuState::Disabled))),
+                    sync_input_focus.run_if(resource_changed::<MenuFocus>),
+                    clear_input_focus.run_if(in_state(MenuState::Disabled)),
+                    relabel_all.run_if(resource_changed::<Messages>),
+                ),
+            )
+            .add_systems(
+                Last,
+                (
+                    (
+                        describe_widgets::<Slider>,
+                        describe_widgets::<Checkbox>,
+                        describe_widgets::<CycleSelect>,
+                        describe_widgets::<Dropdown>,
+                        describe_widgets::<TextField>,
+                    ),
+                    describe_labelled,
+                )
+                    .chain(),
+            );
+
+        #[cfg(debug_assertions)]
+        app.add_systems(Last, validate_accessibility.after(describe_labelled));
+    }
+}
+
+/// Names a node for screen readers, instead of the text inside of it.
+#[derive(Component, Clone, Debug)]
+pub struct AccessibleLabel(pub Localized);
+
+impl AccessibleLabel {
+    pub fn new(label: impl Into<Localized>) -> Self {
+        Self(label.into())
+    }
+}
+
+/// The accessible node of a modal dialog named `label`.
+///
+/// Only the direct children with accessible nodes are put in the dialog,
+/// so this should go on the node holding its text and buttons.
+pub fn dialog(label: impl Into<Localized>) -> impl Bundle {
+    let mut node = Accessible::new(Role::Dialog);
+    node.set_modal();
+    (AccessibilityNode(node), AccessibleLabel::new(label))
+}
+
+/// A widget that describes itself to screen readers.
+pub trait AccessibleWidget: Component {
+    /// The accessible node for the widget's current state, without its name.
+    fn accessible(&self, messages: &Messages) -> Accessible;
+}
+
+impl AccessibleWidget for Slider {
+    fn accessible(&self, _messages: &Messages) -> Accessible {
+        let mut node = Accessible::new(Role::Slider);
+        node.set_numeric_value(self.value as f64);
+        node.set_min_numeric_value(self.min as f64);
+        node.set_max_numeric_value(self.max as f64);
+        node.set_numeric_value_step(self.step as f64);
+        node.set_value((self.format)(self.value));
+        node.add_action(Action::Increment);
+        node.add_action(Action::Decrement);
+        node.add_action(Action::SetValue);
+        node
+    }
+}
+
+impl AccessibleWidget for Checkbox {
+    fn accessible(&self, _messages: &Messages) -> Accessible {
+        let mut node = Accessible::new(Role::CheckBox);
+        node.set_toggled(if self.checked {
+            Toggled::True
+        } else {
+            Toggled::False
+        });
+        node.add_action(Action::Click);
+        node
+    }
+}
+
+impl AccessibleWidget for CycleSelect {
+    fn accessible(&self, messages: &Messages) -> Accessible {
+        let mut node = Accessible::new(Role::SpinButton);
+        if let Some(option) = self.options.get(self.selected) {
+            node.set_value(messages.format(option));
+        }
+        node.add_action(Action::Increment);
+        node.add_action(Action::Decrement);
+        node
+    }
+}
+
+impl AccessibleWidget for Dropdown {
+    fn accessible(&self, messages: &Messages) -> Accessible {
+        let mut node = Accessible::new(Role::ComboBox);
+        if let Some(option) = self.options.get(self.selected) {
+            node.set_value(messages.format(option));
+        }
+        node.add_action(Action::Click);
+        node
+    }
+}
+
+impl AccessibleWidget for TextField {
+    fn accessible(&self, messages: &Messages) -> Accessible {
+        let mut node = Accessible::new(Role::TextInput);
+        node.set_value(self.value.as_str());
+        if !self.placeholder.is_empty() {
+            node.set_placeholder(messages.format(&self.placeholder));
+        }
+        node.add_action(Action::Click);
+        node
+    }
+}
+
+/// Replaces the accessible node of `entity`, keeping the bounds worked out by Bevy.
+fn set_accessible(
+    commands: &mut Commands,
+    entity: Entity,
+    accessible: Option<Mut<AccessibilityNode>>,
+    mut node: Accessible,
+) {
+    match accessible {
+        Some(mut accessible) => {
+            if let Some(bounds) = accessible.bounds() {
+                node.set_bounds(bounds);
+            }
+            accessible.0 = node;
+        }
+        None => {
+            commands.entity(entity).try_insert(AccessibilityNode(node));
+        }
+    }
+}
+
+/// The name of a widget, from its [`AccessibleLabel`] or the [`SettingRow`] it is in.
+fn widget_label<'a>(
+    entity: Entity,
+    labels: &'a Query<&AccessibleLabel>,
+    rows: &'a Query<&SettingRow>,
+    parents: &Query<&ChildOf>,
+) -> Option<&'a Localized> {
+    labels.get(entity).map(|label| &label.0).ok().or_else(|| {
+        parents
+            .iter_ancestors(entity)
+            .find_map(|ancestor| rows.get(ancestor).ok())
+            .map(|row| &row.0)
+    })
+}
+
+fn describe_widgets<W: AccessibleWidget>(
+    mut commands: Commands,
+    mut widgets: Query<
+        (Entity, &W, Option<&mut AccessibilityNode>),
+        Or<(
+            Changed<W>,
+            Changed<Button>,
+            Changed<AccessibleLabel>,
+            Added<AccessibilityNode>,
+        )>,
+    >,
+    labels: Query<&AccessibleLabel>,
+    rows: Query<&SettingRow>,
+    parents: Query<&ChildOf>,
+    messages: Res<Messages>,
+) {
+    for (entity, widget, accessible) in widgets.iter_mut() {
+        let mut node = widget.accessible(&messages);
+        if let Some(label) = widget_label(entity, &labels, &rows, &parents) {
+            node.set_label(messages.format(label));
+        }
+        set_accessible(&mut commands, entity, accessible, node);
+    }
+}
+
+/// Names the other nodes with an [`AccessibleLabel`].
+fn describe_labelled(
+    mut commands: Commands,
+    mut labelled: Query<
+        (Entity, &AccessibleLabel, Option<&mut AccessibilityNode>),
+        (
+            Or<(
+                Changed<AccessibleLabel>,
+                Changed<Button>,
+                Added<AccessibilityNode>,
+            )>,
+            Without<Slider>,
+            Without<Checkbox>,
+            Without<CycleSelect>,
+            Without<Dropdown>,
+            Without<TextField>,
+        ),
+    >,
+    buttons: Query<(), With<Button>>,
+    messages: Res<Messages>,
+) {
+    for (entity, label, accessible) in labelled.iter_mut() {
+        let label = messages.format(&label.0);
+        match accessible {
+            Some(mut accessible) => accessible.set_label(label),
+            None => {
+                let role = if buttons.contains(entity) {
+                    Role::Button
+                } else {
+                    Role::Label
+                };
+                let mut node = Accessible::new(role);
+                node.set_label(label);
+                set_accessible(&mut commands, entity, None, node);
+            }
+        }
+    }
+}
+
+/// Describes everything again in the new language, including the buttons Bevy names from their text.
+fn relabel_all(mut buttons: Query<&mut Button>, mut labels: Query<&mut AccessibleLabel>) {
+    for mut button in buttons.iter_mut() {
+        button.set_changed();
+    }
+    for mut label in labels.iter_mut() {
+        label.set_changed();
+    }
+}
+
+/// Moves the screen reader's focus along with the menu's.
+fn sync_input_focus(focus: Res<MenuFocus>, mut input_focus: ResMut<InputFocus>) {
+    if input_focus.0 != focus.entity {
+        input_focus.0 = focus.entity;
+    }
+}
+
+fn clear_input_focus(mut input_focus: ResMut<InputFocus>) {
+    if input_focus.0.is_some() {
+        input_focus.0 = None;
+    }
+}
+
+/// Carries out the requests of screen readers, such as focusing or clicking a node.
+fn accessibility_actions(
+    mut commands: Commands,
+    mut requests: EventReader<ActionRequest>,
+    mut focus: ResMut<MenuFocus>,
+    buttons: Query<(), With<Button>>,
+    mut sliders: Query<&mut Slider>,
+    mut selects: Query<&mut CycleSelect>,
+    transforms: Query<&GlobalTransform>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    camera: Query<Entity, With<Camera>>,
+) {
+    for request in requests.read() {
+        let Some(target) = Entity::try_from_bits(request.target.0)
+            .ok()
+            .filter(|target| buttons.contains(*target))
+        else {
+            continue;
+        };
+
+        match request.action {
+            Action::Focus => {
+                focus.entity = Some(target);
+                focus.visible = true;
+            }
+            Action::Click => {
+                click_entity(&mut commands, target, &transforms, &window, &camera);
+            }
+            Action::Increment | Action::Decrement => step_widget(
+                &mut commands,
+                target,
+                request.action == Action::Increment,
+                &mut sliders,
+                &mut selects,
+            ),
+            Action::SetValue => {
+                if let (Ok(mut slider), Some(ActionData::NumericValue(value))) =
+                    (sliders.get_mut(target), &request.data)
+                {
+                    set_slider(&mut commands, target, &mut slider, *value as f32);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether screen readers can act on nodes with the role, so they need a name.
+fn is_interactive(role: Role) -> bool {
+    matches!(
+        role,
+        Role::Button
+            | Role::CheckBox
+            | Role::Slider
+            | Role::SpinButton
+            | Role::ComboBox
+            | Role::TextInput
+    )
+}
+
+/// The accessible nodes, and the parents they were spawned under.
+#[cfg(any(debug_assertions, test))]
+type AccessibleNodes<'w, 's> =
+    Query<'w, 's, (Entity, &'static AccessibilityNode, Option<&'static ChildOf>)>;
+
+/// A node of the accessibility tree that a screen reader couldn't describe.
+#[cfg(any(debug_assertions, test))]
+struct AccessibilityProblem {
+    entity: Entity,
+    role: Role,
+    /// The role and name of each node above this one.
+    path: Vec<String>,
+    problems: Vec<&'static str>,
+}
+
+#[cfg(any(debug_assertions, test))]
+impl std::fmt::Display for AccessibilityProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the {:?} at {} {}",
+            self.role,
+            self.path.join(" > "),
+            self.problems.join(" and ")
+        )
+    }
+}
+
+/// Walks the accessibility tree, finding every node that is missing something a screen
+/// reader needs, such as a name for something that can be acted on.
+#[cfg(any(debug_assertions, test))]
+fn accessibility_problems(
+    nodes: &AccessibleNodes,
+    children: &Query<&Children>,
+) -> Vec<AccessibilityProblem> {
+    // Like the tree given to screen readers, nodes belong to
+    // their parent only if it is in the tree too.
+    let roots = nodes.iter().filter(|(_, _, child_of)| {
+        child_of.is_none_or(|child_of| !nodes.contains(child_of.parent()))
+    });
+
+    let mut stack = roots
+        .map(|(entity, _, _)| (entity, Vec::<String>::new()))
+        .collect::<Vec<_>>();
+    let mut found = vec![];
+
+    while let Some((entity, path)) = stack.pop() {
+        let Ok((_, node, _)) = nodes.get(entity) else {
+            continue;
+        };
+
+        let name = node
+            .label()
+            .or(node.value())
+            .unwrap_or_default()
+            .to_string();
+        let mut problems = vec![];
+
+        if is_interactive(node.role()) && name.trim().is_empty() {
+            problems.push("has no name");
+        }
+        let has_value = match node.role() {
+            Role::Slider => node.numeric_value().is_some(),
+            Role::SpinButton | Role::ComboBox => node.value().is_some(),
+            _ => true,
+        };
+        if !has_value {
+            problems.push("has no value");
+        }
+        if node.role() == Role::CheckBox && node.toggled().is_none() {
+            problems.push("is neither checked nor unchecked");
+        }
+
+        let mut child_path = path.clone();
+        child_path.push(format!("{:?} {name:?}", node.role()));
+        stack.extend(
+            children
+                .get(entity)
+                .into_iter()
+                .flatten()
+                .filter(|child| nodes.contains(**child))
+                .map(|child| (*child, child_path.clone())),
+        );
+
+        if !problems.is_empty() {
+            found.push(AccessibilityProblem {
+                entity,
+                role: node.role(),
+                path,
+                problems,
+            });
+        }
+    }
+
+    found
+}
+
+/// Checks the accessibility tree of each menu screen as it is built,
+/// warning about anything a screen reader couldn't describe.
+#[cfg(debug_assertions)]
+fn validate_accessibility(
+    menu_state: Res<State<MenuState>>,
+    added: Query<(), Added<AccessibilityNode>>,
+    nodes: AccessibleNodes,
+    children: Query<&Children>,
+    mut warned: Local<bevy::platform::collections::HashSet<Entity>>,
+) {
+    if added.is_empty() {
+        return;
+    }
+
+    for problem in accessibility_problems(&nodes, &children) {
+        if warned.insert(problem.entity) {
+            warn!("In the {:?} menu, {problem}", menu_state.get());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless_app;
+    use bevy::ecs::system::RunSystemOnce;
+    use core::time::Duration;
+
+    /// Every menu other than [`MenuState::Disabled`].
+    const MENUS: [MenuState; 9] = [
+        MenuState::Main,
+        MenuState::Settings,
+        MenuState::Display,
+        MenuState::Sound,
+        MenuState::Camera,
+        MenuState::Sky,
+        MenuState::Theme,
+        MenuState::Controls,
+        MenuState::Pause,
+    ];
+
+    /// Updates `app` until the main menu opens, once the message catalogs have loaded.
+    fn open_menus(app: &mut App) {
+        for _ in 0..500 {
+            app.update();
+            if *app.world().resource::<State<MenuState>>() == MenuState::Main {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("The main menu never opened");
+    }
+
+    #[test]
+    fn every_menu_is_accessible() {
+        let mut app = headless_app();
+        open_menus(&mut app);
+
+        for menu in MENUS {
+            app.world_mut()
+                .resource_mut::<NextState<MenuState>>()
+                .set(menu);
+            // The menu is spawned, written out in the language, then described.
+            for _ in 0..3 {
+                app.update();
+            }
+
+            let problems = app
+                .world_mut()
+                .run_system_once(|nodes: AccessibleNodes, children: Query<&Children>| {
+                    accessibility_problems(&nodes, &children)
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap();
+            assert!(problems.is_empty(), "In the {menu:?} menu, {problems:#?}");
+
+            let interactive = app
+                .world_mut()
+                .run_system_once(|nodes: AccessibleNodes| {
+                    nodes
+                        .iter()
+                        .filter(|(_, node, _)| is_interactive(node.role()))
+                        .count()
+                })
+                .unwrap();
+            assert!(interactive > 0, "The {menu:?} menu has nothing to act on");
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use super::*;
+use crate::camera::{CameraMouseSettings, CameraMovementSettings};
+use crate::prelude::*;
+
+use bevy::prelude::*;
+
+pub struct MenuCameraPlugin;
+
+impl Plugin for MenuCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Camera), camera_enter)
+            .add_systems(
+                OnExit(MenuState::Camera),
+                despawn_all_with::<OnCameraScreen>,
+            );
+    }
+}
+
+#[derive(Component)]
+struct OnCameraScreen;
+
+fn camera_enter(
+    mut commands: Commands,
+    style: Res<Style>,
+    mouse: Res<CameraMouseSettings>,
+    movement: Res<CameraMovementSettings>,
+) {
+    commands
+        .spawn(menu_root(OnCameraScreen))
+        .with_children(|builder| {
+            builder.spawn(menu_column()).with_children(|builder| {
+                settings_rows(builder, &style, &*mouse);
+                settings_rows(builder, &style, &*movement);
+
+                button(builder, &style, "menu.back", 200.0)
+                    .insert(MenuButtonAction::Settings)
+                    .observe(menu_button_click);
+            });
+        });
+}
@@ -14,7 +14,8 @@ use bevy::{
 };
 
 use crate::controls::Control;
-use crate::controls::{Input, Keybind, input_to_screen};
+use crate::controls::{ControlsError, Input, Keybind, input_to_screen};
+use crate::glyphs::{GamepadGlyphs, GlyphPreference};
 
 pub struct MenuControlsPlugin;
 
@@ -36,7 +37,13 @@ impl Plugin for MenuControlsPlugin {
             .add_systems(
                 Update,
                 (
-                    controls_changed.run_if(resource_exists_and_changed::<ControlsWIP>),
+                    controls_changed
+                        .run_if(
+                            resource_exists_and_changed::<ControlsWIP>
+                                .or(resource_changed::<GamepadGlyphs>),
+                        )
+                        .before(button_highlight),
+                    glyph_preference_changed.run_if(resource_changed::<GlyphPreference>),
                     escape_out,
                 )
                     .run_if(in_state(MenuState::Controls)),
@@ -60,6 +67,14 @@ impl Plugin for MenuControlsPlugin {
             .add_systems(
                 OnExit(ControlsState::SaveWarning),
                 despawn_all_with::<OnSaveWarning>,
+            )
+            .add_systems(OnEnter(ControlsState::Conflict), control_conflict_enter)
+            .add_systems(
+                OnExit(ControlsState::Conflict),
+                (
+                    despawn_all_with::<OnConflict>,
+                    remove_resource::<PendingBind>,
+                ),
             );
     }
 }
@@ -70,6 +85,7 @@ pub enum ControlsState {
     Main,
     Prompt,
     SaveWarning,
+    Conflict,
 }
 
 #[derive(Component)]
@@ -81,6 +97,24 @@ pub struct OnPrompt;
 #[derive(Component)]
 pub struct OnSaveWarning;
 
+#[derive(Component)]
+pub struct OnConflict;
+
+/// Marks the text used to tell the user why an action failed.
+#[derive(Component)]
+pub struct ControlsStatus;
+
+/// An input waiting on the user to decide how to resolve a conflict.
+#[derive(Resource)]
+pub struct PendingBind {
+    /// The entry the user is trying to bind.
+    target: (Control, usize),
+    /// The input the user wants to bind.
+    input: Input,
+    /// The entry already bound to `input`.
+    other: (Control, usize),
+}
+
 #[derive(Component, Clone, Debug)]
 pub enum ControlsButtonAction {
     Prompt(Control, usize),
@@ -89,9 +123,19 @@ pub enum ControlsButtonAction {
     ResetAll,
     Save,
     Discard,
+    SaveAndExit,
+    DiscardAndExit,
+    ConflictSwap,
+    ConflictClear,
+    ConflictKeep,
+    CycleGlyphs,
     Back,
 }
 
+/// Marks the text showing the current [`GlyphPreference`].
+#[derive(Component)]
+pub struct GlyphPreferenceText;
+
 fn escape_out(
     controls_state: Res<State<ControlsState>>,
     mut next_controls_state: ResMut<NextState<ControlsState>>,
@@ -109,6 +153,9 @@ fn escape_out(
             C::SaveWarning => {
                 next_menu_state.set(MenuState::Settings);
             }
+            C::Conflict => {
+                next_controls_state.set(ControlsState::Main);
+            }
             C::Main => {
                 if controls_wip.0 == *controls_master {
                     next_menu_state.set(MenuState::Settings);
@@ -120,7 +167,13 @@ fn escape_out(
     }
 }
 
-fn controls_enter(mut commands: Commands, style: Res<Style>, controls: Res<Controls>) {
+fn controls_enter(
+    mut commands: Commands,
+    style: Res<Style>,
+    glyphs: Res<GamepadGlyphs>,
+    preference: Res<GlyphPreference>,
+    controls: Res<Controls>,
+) {
     let button_node = Node {
         width: Val::Px(200.0),
         height: Val::Px(65.0),
@@ -131,8 +184,7 @@ fn controls_enter(mut commands: Commands, style: Res<Style>, controls: Res<Contr
     };
 
     let button_text_style = (
-        style.font(33.0),
-        TextColor(style.text_color),
+        style.text(StyleRole::Text, TextSize::Body),
         TextLayout::new_with_justify(JustifyText::Center),
     );
 
@@ -170,7 +222,7 @@ fn controls_enter(mut commands: Commands, style: Res<Style>, controls: Res<Contr
                     controls
                         .clone()
                         .into_iter()
-                        .for_each(|keybind| controls_row(builder, &style, keybind))
+                        .for_each(|keybind| controls_row(builder, &style, &glyphs, keybind))
                 });
 
             builder
@@ -186,25 +238,30 @@ fn controls_enter(mut commands: Commands, style: Res<Style>, controls: Res<Contr
                         ..default()
                     },
                     FocusPolicy::Block,
-                    BackgroundColor(style.background_color),
+                    style.background(StyleRole::Background),
                 ))
                 .with_children(|builder| {
-                    builder.spawn((
-                        Button,
-                        button_node.clone(),
-                        BackgroundColor(style.button_color),
-                        ControlsButtonAction::Back,
-                        children![(Text::new("Back"), button_text_style.clone(), Pickable::IGNORE)],
-                    ))
+                    builder
+                        .spawn((
+                            Button,
+                            button_node.clone(),
+                            style.background(StyleRole::Button),
+                            ControlsButtonAction::Back,
+                            children![(
+                                localized("menu.back"),
+                                button_text_style.clone(),
+                                Pickable::IGNORE
+                            )],
+                        ))
                         .observe(controls_menu_click);
 
                     builder
                         .spawn((
                             Button,
                             button_node.clone(),
-                            BackgroundColor(style.button_color),
+                            style.background(StyleRole::Button),
                             ControlsButtonAction::Save,
-                            children![(Text::new("Save"), button_text_style.clone())],
+                            children![(localized("controls.save"), button_text_style.clone())],
                         ))
                         .observe(controls_menu_click);
 
@@ -212,9 +269,9 @@ fn controls_enter(mut commands: Commands, style: Res<Style>, controls: Res<Contr
                         .spawn((
                             Button,
                             button_node.clone(),
-                            BackgroundColor(style.button_color),
+                            style.background(StyleRole::Button),
                             ControlsButtonAction::Discard,
-                            children![(Text::new("Discard"), button_text_style.clone())],
+                            children![(localized("controls.discard"), button_text_style.clone())],
                         ))
                         .observe(controls_menu_click);
 
@@ -222,18 +279,38 @@ fn controls_enter(mut commands: Commands, style: Res<Style>, controls: Res<Contr
                         .spawn((
                             Button,
                             button_node.clone(),
-                            BackgroundColor(style.button_color),
+                            style.background(StyleRole::Button),
                             ControlsButtonAction::ResetAll,
-                            children![(Text::new("Reset All"), button_text_style.clone())],
+                            children![(localized("controls.reset_all"), button_text_style.clone())],
+                        ))
+                        .observe(controls_menu_click);
+
+                    builder
+                        .spawn((
+                            Button,
+                            button_node.clone(),
+                            style.background(StyleRole::Button),
+                            ControlsButtonAction::CycleGlyphs,
+                            children![(
+                                localized(glyphs_text(*preference)),
+                                button_text_style.clone(),
+                                GlyphPreferenceText,
+                                Pickable::IGNORE
+                            )],
                         ))
                         .observe(controls_menu_click);
 
                     builder.spawn((
-                        Text::new(
-                            "Note: The keys show are based on the physical key and may not reflect the keyboard input in a text box.",
-                        ),
+                        localized(Localized::default()),
+                        style.text(StyleRole::Warning, TextSize::Caption),
+                        ControlsStatus,
+                        Pickable::IGNORE,
+                    ));
+
+                    builder.spawn((
+                        localized("controls.note"),
                         (
-                            style.font(18.0),
+                            style.text(StyleRole::Text, TextSize::Small),
                             TextLayout::new_with_justify(JustifyText::Center),
                         ),
                         Pickable::IGNORE,
@@ -242,10 +319,21 @@ fn controls_enter(mut commands: Commands, style: Res<Style>, controls: Res<Contr
         });
 }
 
-fn controls_row(builder: &mut ChildSpawnerCommands<'_>, style: &Style, keybind: Keybind) {
+fn controls_row(
+    builder: &mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    glyphs: &GamepadGlyphs,
+    keybind: Keybind,
+) {
     let Keybind(control, keys) = keybind;
+
     builder
-        .spawn((Node::default(), Pickable::IGNORE))
+        .spawn((
+            Node::default(),
+            AccessibilityNode(Accessible::new(Role::ListItem)),
+            AccessibleLabel::new(control),
+            Pickable::IGNORE,
+        ))
         .with_children(|builder| {
             builder
                 .spawn((
@@ -255,15 +343,13 @@ fn controls_row(builder: &mut ChildSpawnerCommands<'_>, style: &Style, keybind:
                         align_items: AlignItems::Center,
                         ..default()
                     },
-                    Label,
-                    AccessibilityNode(Accessible::new(Role::ListItem)),
                     Pickable::IGNORE,
                 ))
                 .with_children(|builder| {
                     builder.spawn((
-                        Text::new(control.to_string()),
-                        TextColor(style.title_color),
-                        style.font(33.0),
+                        localized(control),
+                        style.text(StyleRole::Title, TextSize::Body),
+                        Label,
                         Pickable::IGNORE,
                     ));
                 });
@@ -281,16 +367,16 @@ fn controls_row(builder: &mut ChildSpawnerCommands<'_>, style: &Style, keybind:
                             overflow: Overflow::clip(),
                             ..default()
                         },
-                        BackgroundColor(style.button_color),
+                        style.background(StyleRole::Button),
                         ControlsButtonAction::Prompt(control, i),
-                        AccessibilityNode(Accessible::new(Role::ListItem)),
+                        binding_label(control, i, key),
                         Pickable {
                             should_block_lower: false,
                             is_hoverable: true,
                         },
                     ))
                     .observe(controls_menu_click)
-                    .with_children(|builder| input_to_screen(style, builder, &key));
+                    .with_children(|builder| input_to_screen(style, glyphs, builder, &key));
             }
 
             builder
@@ -305,17 +391,18 @@ fn controls_row(builder: &mut ChildSpawnerCommands<'_>, style: &Style, keybind:
                         overflow: Overflow::clip(),
                         ..default()
                     },
-                    BackgroundColor(style.button_color),
+                    style.background(StyleRole::Button),
                     ControlsButtonAction::ResetBoth(control),
-                    AccessibilityNode(Accessible::new(Role::ListItem)),
+                    AccessibleLabel::new(
+                        Localized::new("controls.reset_control").with("control", control),
+                    ),
                     Pickable {
                         should_block_lower: false,
                         is_hoverable: true,
                     },
                     children![(
-                        Text("Reset Both".into()),
-                        style.font(33.0),
-                        TextColor(style.text_color)
+                        localized("controls.reset"),
+                        style.text(StyleRole::Text, TextSize::Body),
                     )],
                 ))
                 .observe(controls_menu_click);
@@ -342,6 +429,9 @@ fn controls_menu_click(
     mut commands: Commands,
     mut controls_master: ResMut<Controls>,
     mut controls_wip: ResMut<ControlsWIP>,
+    mut glyph_preference: ResMut<GlyphPreference>,
+    pending: Option<Res<PendingBind>>,
+    mut status: Query<&mut Localized, With<ControlsStatus>>,
     target_query: Query<&ControlsButtonAction>,
 ) {
     if let Ok(action) = target_query.get(click.target()) {
@@ -372,15 +462,85 @@ fn controls_menu_click(
             (_, C::ResetAll) => {}
 
             (P::Primary, C::Save) => {
-                *controls_master = controls_wip.0.clone();
+                if let Err(err) = controls_wip.0.validate() {
+                    set_status(&mut status, cannot_save_text(err));
+                } else {
+                    *controls_master = controls_wip.0.clone();
+                    set_status(&mut status, Localized::default());
+                }
             }
             (_, C::Save) => {}
 
             (P::Primary, C::Discard) => {
                 controls_wip.0 = controls_master.clone();
+                set_status(&mut status, Localized::default());
             }
             (_, C::Discard) => {}
 
+            (P::Primary, C::SaveAndExit) => {
+                if let Err(err) = controls_wip.0.validate() {
+                    set_status(&mut status, cannot_save_text(err));
+                    commands.set_state(ControlsState::Main);
+                } else {
+                    *controls_master = controls_wip.0.clone();
+                    commands.set_state(MenuState::Settings);
+                }
+            }
+            (_, C::SaveAndExit) => {}
+
+            (P::Primary, C::DiscardAndExit) => {
+                controls_wip.0 = controls_master.clone();
+                commands.set_state(MenuState::Settings);
+            }
+            (_, C::DiscardAndExit) => {}
+
+            (P::Primary, C::ConflictSwap) => {
+                if let Some(pending) = pending {
+                    let (control, entry) = pending.target;
+                    let (other_control, other_entry) = pending.other;
+                    let previous = controls_wip.0.get_control_part(control, entry);
+
+                    controls_wip
+                        .0
+                        .set_control(other_control, other_entry, previous);
+                    controls_wip
+                        .0
+                        .set_control(control, entry, Some(pending.input));
+                }
+                commands.set_state(ControlsState::Main);
+            }
+            (_, C::ConflictSwap) => {}
+
+            (P::Primary, C::ConflictClear) => {
+                if let Some(pending) = pending {
+                    let (control, entry) = pending.target;
+                    let (other_control, other_entry) = pending.other;
+
+                    controls_wip.0.set_control(other_control, other_entry, None);
+                    controls_wip
+                        .0
+                        .set_control(control, entry, Some(pending.input));
+                }
+                commands.set_state(ControlsState::Main);
+            }
+            (_, C::ConflictClear) => {}
+
+            (P::Primary, C::ConflictKeep) => {
+                if let Some(pending) = pending {
+                    let (control, entry) = pending.target;
+                    controls_wip
+                        .0
+                        .set_control(control, entry, Some(pending.input));
+                }
+                commands.set_state(ControlsState::Main);
+            }
+            (_, C::ConflictKeep) => {}
+
+            (P::Primary, C::CycleGlyphs) => {
+                *glyph_preference = glyph_preference.next();
+            }
+            (_, C::CycleGlyphs) => {}
+
             (P::Primary, C::Back) => {
                 if controls_wip.0 == *controls_master {
                     commands.set_state(MenuState::Settings);
@@ -395,16 +555,69 @@ fn controls_menu_click(
     click.propagate(false);
 }
 
+/// What screen readers call the button for an entry of a control,
+/// as the glyphs on it have no text.
+fn binding_label(control: Control, entry: usize, key: Option<Input>) -> AccessibleLabel {
+    AccessibleLabel::new(
+        Localized::new("controls.binding")
+            .with("control", control)
+            .with("number", Localized::verbatim((entry + 1).to_string()))
+            .with(
+                "input",
+                key.map_or_else(|| Localized::new("controls.unbound"), Localized::from),
+            ),
+    )
+}
+
+fn cannot_save_text(err: ControlsError) -> Localized {
+    Localized::new("controls.cannot_save").with("error", err)
+}
+
+fn glyphs_text(preference: GlyphPreference) -> Localized {
+    Localized::new("controls.glyphs").with("glyphs", preference)
+}
+
+fn set_status(status: &mut Query<&mut Localized, With<ControlsStatus>>, message: Localized) {
+    for mut text in status.iter_mut() {
+        *text = message.clone();
+    }
+}
+
+fn glyph_preference_changed(
+    preference: Res<GlyphPreference>,
+    mut text: Query<&mut Localized, With<GlyphPreferenceText>>,
+) {
+    for mut text in text.iter_mut() {
+        *text = glyphs_text(*preference);
+    }
+}
+
 fn controls_changed(
     mut commands: Commands,
     style: Res<Style>,
+    glyphs: Res<GamepadGlyphs>,
     controls: Res<ControlsWIP>,
-    button: Query<(Entity, &ControlsButtonAction, &Children)>,
+    mut button: Query<(Entity, &ControlsButtonAction, &Children, &mut Interaction)>,
 ) {
-    for (entity, action, children) in button.iter() {
+    let conflicts = controls.0.conflicts();
+
+    for (entity, action, children, mut interaction) in button.iter_mut() {
         use ControlsButtonAction as C;
         if let C::Prompt(control, idx) = action {
             let key = controls.0.get_control_part(*control, *idx);
+
+            commands
+                .entity(entity)
+                .insert(binding_label(*control, *idx, key));
+
+            if conflicts.contains(&(*control, *idx)) {
+                commands.entity(entity).insert(Conflicting);
+            } else {
+                commands.entity(entity).remove::<Conflicting>();
+            }
+            // Have `button_highlight` color it, along with its hover and selection.
+            interaction.set_changed();
+
             for child in children {
                 if let Ok(mut child) = commands.get_entity(*child) {
                     child.despawn();
@@ -414,15 +627,14 @@ fn controls_changed(
                 .get_entity(entity)
                 .expect("It was just clicked, it should be alive?")
                 .remove_children(children)
-                .with_children(|builder| input_to_screen(&style, builder, &key));
+                .with_children(|builder| input_to_screen(&style, &glyphs, builder, &key));
         }
     }
 }
 
 fn control_prompt_enter(mut commands: Commands, style: Res<Style>) {
     let button_text_style = (
-        style.font(33.0),
-        TextColor(style.text_color),
+        style.text(StyleRole::Text, TextSize::Body),
         TextLayout::new_with_justify(JustifyText::Center),
     );
 
@@ -437,8 +649,9 @@ fn control_prompt_enter(mut commands: Commands, style: Res<Style>) {
             ..default()
         },
         FocusPolicy::Block,
+        FocusTrap,
         OnPrompt,
-        BackgroundColor(style.background_color.with_alpha(1.0)),
+        style.background(StyleRole::Dialog),
         ZIndex(2),
         children![(
             Node {
@@ -450,20 +663,21 @@ fn control_prompt_enter(mut commands: Commands, style: Res<Style>) {
                 align_items: AlignItems::Center,
                 ..default()
             },
+            dialog("controls.prompt.title"),
             children![
                 (
-                    Text::new("Press any key to bind,"),
-                    style.font(33.0),
-                    TextColor(style.text_color),
+                    localized("controls.prompt.press"),
+                    style.text(StyleRole::Text, TextSize::Body),
+                    Label,
                     Node {
                         margin: UiRect::all(Val::Px(50.0)),
                         ..default()
                     },
                 ),
                 (
-                    Text::new("or click 'Cancel'"),
-                    style.font(33.0),
-                    TextColor(style.text_color),
+                    localized("controls.prompt.or_cancel"),
+                    style.text(StyleRole::Text, TextSize::Body),
+                    Label,
                     Node {
                         margin: UiRect::all(Val::Px(50.0)),
                         ..default()
@@ -480,10 +694,10 @@ fn control_prompt_enter(mut commands: Commands, style: Res<Style>) {
                         align_self: AlignSelf::Center,
                         ..default()
                     },
-                    BackgroundColor(style.button_color),
+                    style.background(StyleRole::Button),
                     ControlsButtonAction::PromptCancel,
                     children![(
-                        Text::new("Cancel"),
+                        localized("controls.cancel"),
                         button_text_style.clone(),
                         ControlsButtonAction::PromptCancel
                     )],
@@ -493,6 +707,32 @@ fn control_prompt_enter(mut commands: Commands, style: Res<Style>) {
     ));
 }
 
+/// Binds `input` to the prompt's target, unless it is already bound
+/// elsewhere, in which case the user is asked how to resolve it.
+fn bind_input(
+    commands: &mut Commands,
+    controls: &mut ControlsWIP,
+    target: &PromptTarget,
+    input: Input,
+) {
+    let target = (target.0, target.1);
+
+    match controls.0.find_input(input, target) {
+        Some(other) => {
+            commands.insert_resource(PendingBind {
+                target,
+                input,
+                other,
+            });
+            commands.set_state(ControlsState::Conflict);
+        }
+        None => {
+            controls.0.set_control(target.0, target.1, Some(input));
+            commands.set_state(ControlsState::Main);
+        }
+    }
+}
+
 fn assign_key_input(
     mut commands: Commands,
     mut keyboard: EventReader<KeyboardInput>,
@@ -506,10 +746,12 @@ fn assign_key_input(
     for ev in keyboard.read() {
         match ev.state {
             ButtonState::Pressed => {
-                controls
-                    .0
-                    .set_control(target.0, target.1, Some(Input::Keyboard(ev.key_code)));
-                commands.set_state(ControlsState::Main);
+                bind_input(
+                    &mut commands,
+                    &mut controls,
+                    &target,
+                    Input::Keyboard(ev.key_code),
+                );
                 return;
             }
             ButtonState::Released => {}
@@ -530,10 +772,12 @@ fn assign_key_input(
                     }
                 }
 
-                controls
-                    .0
-                    .set_control(target.0, target.1, Some(Input::Mouse(ev.button)));
-                commands.set_state(ControlsState::Main);
+                bind_input(
+                    &mut commands,
+                    &mut controls,
+                    &target,
+                    Input::Mouse(ev.button),
+                );
                 return;
             }
             ButtonState::Released => {}
@@ -543,10 +787,12 @@ fn assign_key_input(
     for ev in gamepad.read() {
         match ev.state {
             ButtonState::Pressed => {
-                controls
-                    .0
-                    .set_control(target.0, target.1, Some(Input::Gamepad(ev.button)));
-                commands.set_state(ControlsState::Main);
+                bind_input(
+                    &mut commands,
+                    &mut controls,
+                    &target,
+                    Input::Gamepad(ev.button),
+                );
                 return;
             }
             ButtonState::Released => {}
@@ -556,8 +802,7 @@ fn assign_key_input(
 
 fn control_save_warning_enter(mut commands: Commands, style: Res<Style>) {
     let button_text_style = (
-        style.font(33.0),
-        TextColor(style.text_color),
+        style.text(StyleRole::Text, TextSize::Body),
         TextLayout::new_with_justify(JustifyText::Center),
     );
 
@@ -573,22 +818,36 @@ fn control_save_warning_enter(mut commands: Commands, style: Res<Style>) {
                 ..default()
             },
             FocusPolicy::Block,
+            FocusTrap,
             OnSaveWarning,
-            BackgroundColor(style.background_color.with_alpha(1.0)),
+            style.background(StyleRole::Dialog),
             ZIndex(2),
         ))
         .with_children(|builder| {
             builder
-                .spawn(Node {
-                    display: Display::Flex,
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    flex_direction: FlexDirection::Column,
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    ..default()
-                })
+                .spawn((
+                    Node {
+                        display: Display::Flex,
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    dialog("controls.unsaved.title"),
+                ))
                 .with_children(|builder| {
+                    builder.spawn((
+                        localized("controls.unsaved.message"),
+                        style.text(StyleRole::Text, TextSize::Body),
+                        Label,
+                        Node {
+                            margin: UiRect::all(Val::Px(20.0)),
+                            ..default()
+                        },
+                    ));
+
                     builder
                         .spawn((
                             Button,
@@ -601,17 +860,15 @@ fn control_save_warning_enter(mut commands: Commands, style: Res<Style>) {
                                 align_self: AlignSelf::Center,
                                 ..default()
                             },
-                            BackgroundColor(style.button_color),
-                            ControlsButtonAction::Save,
-                            MenuButtonAction::Settings,
+                            style.background(StyleRole::Button),
+                            ControlsButtonAction::SaveAndExit,
                             children![(
-                                Text::new("Save Changes"),
+                                localized("controls.unsaved.save"),
                                 button_text_style.clone(),
-                                ControlsButtonAction::PromptCancel
+                                Pickable::IGNORE
                             )],
                         ))
-                        .observe(controls_menu_click)
-                        .observe(menu_button_click);
+                        .observe(controls_menu_click);
                     builder
                         .spawn((
                             Button,
@@ -624,17 +881,140 @@ fn control_save_warning_enter(mut commands: Commands, style: Res<Style>) {
                                 align_self: AlignSelf::Center,
                                 ..default()
                             },
-                            BackgroundColor(style.button_color),
-                            ControlsButtonAction::Discard,
-                            MenuButtonAction::Settings,
+                            style.background(StyleRole::Button),
+                            ControlsButtonAction::DiscardAndExit,
                             children![(
-                                Text::new("Discard Changes"),
+                                localized("controls.unsaved.discard"),
                                 button_text_style.clone(),
-                                ControlsButtonAction::PromptCancel
+                                Pickable::IGNORE
                             )],
                         ))
-                        .observe(controls_menu_click)
-                        .observe(menu_button_click);
+                        .observe(controls_menu_click);
+                });
+        });
+}
+
+fn control_conflict_enter(mut commands: Commands, style: Res<Style>, pending: Res<PendingBind>) {
+    let button_node = Node {
+        width: Val::Px(200.0),
+        height: Val::Px(65.0),
+        margin: UiRect::all(Val::Px(5.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        align_self: AlignSelf::Center,
+        ..default()
+    };
+
+    let button_text_style = (
+        style.text(StyleRole::Text, TextSize::Body),
+        TextLayout::new_with_justify(JustifyText::Center),
+    );
+
+    let (control, _) = pending.target;
+    let (other_control, _) = pending.other;
+
+    commands
+        .spawn((
+            Node {
+                display: Display::Flex,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                align_self: AlignSelf::Center,
+                ..default()
+            },
+            FocusPolicy::Block,
+            FocusTrap,
+            OnConflict,
+            style.background(StyleRole::Dialog),
+            ZIndex(2),
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn((
+                    Node {
+                        display: Display::Flex,
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    dialog("controls.conflict.title"),
+                ))
+                .with_children(|builder| {
+                    builder.spawn((
+                        localized(
+                            Localized::new("controls.conflict.message")
+                                .with("input", pending.input)
+                                .with("control", other_control),
+                        ),
+                        style.text(StyleRole::Text, TextSize::Body),
+                        Label,
+                        Node {
+                            margin: UiRect::all(Val::Px(20.0)),
+                            ..default()
+                        },
+                    ));
+
+                    builder
+                        .spawn(Node {
+                            flex_direction: FlexDirection::Row,
+                            ..default()
+                        })
+                        .with_children(|builder| {
+                            [
+                                (
+                                    ControlsButtonAction::ConflictSwap,
+                                    Localized::new("controls.conflict.swap"),
+                                ),
+                                (
+                                    ControlsButtonAction::ConflictClear,
+                                    Localized::new("controls.conflict.unbind")
+                                        .with("control", other_control),
+                                ),
+                                (
+                                    ControlsButtonAction::ConflictKeep,
+                                    Localized::new("controls.conflict.keep_both"),
+                                ),
+                                (
+                                    ControlsButtonAction::PromptCancel,
+                                    Localized::new("controls.cancel"),
+                                ),
+                            ]
+                            .into_iter()
+                            .for_each(|(action, text)| {
+                                builder
+                                    .spawn((
+                                        Button,
+                                        button_node.clone(),
+                                        style.background(StyleRole::Button),
+                                        action,
+                                        children![(
+                                            localized(text),
+                                            button_text_style.clone(),
+                                            Pickable::IGNORE
+                                        )],
+                                    ))
+                                    .observe(controls_menu_click);
+                            });
+                        });
+
+                    builder.spawn((
+                        localized(
+                            Localized::new("controls.conflict.swap_note")
+                                .with("other", other_control)
+                                .with("control", control),
+                        ),
+                        style.text(StyleRole::Text, TextSize::Caption),
+                        Label,
+                        Node {
+                            margin: UiRect::all(Val::Px(20.0)),
+                            ..default()
+                        },
+                    ));
                 });
         });
 }
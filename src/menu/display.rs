@@ -0,0 +1,135 @@
+use super::*;
+use crate::display::{DisplayMode, DisplaySettings, RESOLUTIONS, SCALE_FACTORS, UI_SCALES};
+use crate::prelude::*;
+
+use bevy::prelude::*;
+
+pub struct MenuDisplayPlugin;
+
+impl Plugin for MenuDisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Display), display_enter)
+            .add_systems(OnExit(MenuState::Display), despawn_all_with::<OnDisplay>);
+    }
+}
+
+#[derive(Component)]
+struct OnDisplay;
+
+/// The index of `current` in `options`, or the first if `current` isn't an option.
+pub(super) fn option_index<T: PartialEq>(options: &[T], current: &T) -> usize {
+    options
+        .iter()
+        .position(|option| option == current)
+        .unwrap_or_default()
+}
+
+fn display_enter(mut commands: Commands, style: Res<Style>, settings: Res<DisplaySettings>) {
+    commands
+        .spawn(menu_root(OnDisplay))
+        .with_children(|builder| {
+            builder.spawn(menu_column()).with_children(|builder| {
+                setting_row(builder, &style, "display.window_mode", |builder| {
+                    cycle_select(
+                        builder,
+                        &style,
+                        CycleSelect::new(
+                            DisplayMode::ALL,
+                            option_index(&DisplayMode::ALL, &settings.mode),
+                        ),
+                    )
+                    .observe(
+                        |changed: Trigger<SelectChanged>, mut settings: ResMut<DisplaySettings>| {
+                            settings.mode = DisplayMode::ALL[changed.0];
+                        },
+                    );
+                });
+
+                setting_row(builder, &style, "display.resolution", |builder| {
+                    dropdown(
+                        builder,
+                        &style,
+                        Dropdown::new(
+                            RESOLUTIONS.map(|(width, height)| {
+                                Localized::verbatim(format!("{width}x{height}"))
+                            }),
+                            option_index(&RESOLUTIONS, &settings.resolution),
+                        ),
+                    )
+                    .observe(
+                        |changed: Trigger<SelectChanged>, mut settings: ResMut<DisplaySettings>| {
+                            settings.resolution = RESOLUTIONS[changed.0];
+                        },
+                    );
+                });
+
+                setting_row(builder, &style, "display.scale_factor", |builder| {
+                    cycle_select(
+                        builder,
+                        &style,
+                        CycleSelect::new(
+                            SCALE_FACTORS.map(|scale_factor| match scale_factor {
+                                Some(scale_factor) => {
+                                    Localized::verbatim(format!("{scale_factor}x"))
+                                }
+                                None => Localized::new("display.auto"),
+                            }),
+                            option_index(&SCALE_FACTORS, &settings.scale_factor),
+                        ),
+                    )
+                    .observe(
+                        |changed: Trigger<SelectChanged>, mut settings: ResMut<DisplaySettings>| {
+                            settings.scale_factor = SCALE_FACTORS[changed.0];
+                        },
+                    );
+                });
+
+                setting_row(builder, &style, "display.vsync", |builder| {
+                    checkbox(builder, &style, settings.vsync).observe(
+                        |changed: Trigger<CheckboxChanged>,
+                         mut settings: ResMut<DisplaySettings>| {
+                            settings.vsync = changed.0;
+                        },
+                    );
+                });
+
+                setting_row(builder, &style, "display.ui_scale", |builder| {
+                    cycle_select(
+                        builder,
+                        &style,
+                        CycleSelect::new(
+                            UI_SCALES.map(|scale| Localized::verbatim(format!("{scale}x"))),
+                            option_index(&UI_SCALES, &settings.ui_scale),
+                        ),
+                    )
+                    .observe(
+                        |changed: Trigger<SelectChanged>, mut settings: ResMut<DisplaySettings>| {
+                            settings.ui_scale = UI_SCALES[changed.0];
+                        },
+                    );
+                });
+
+                setting_row(builder, &style, "display.pixel_perfect", |builder| {
+                    checkbox(builder, &style, settings.pixel_perfect).observe(
+                        |changed: Trigger<CheckboxChanged>,
+                         mut settings: ResMut<DisplaySettings>| {
+                            settings.pixel_perfect = changed.0;
+                        },
+                    );
+                });
+
+                setting_row(builder, &style, "display.reduce_motion", |builder| {
+                    checkbox(builder, &style, settings.reduce_motion).observe(
+                        |changed: Trigger<CheckboxChanged>,
+                         mut settings: ResMut<DisplaySettings>| {
+                            settings.reduce_motion = changed.0;
+                        },
+                    );
+                });
+
+                button(builder, &style, "menu.back", 200.0)
+                    .insert(MenuButtonAction::Settings)
+                    .observe(menu_button_click);
+            });
+        });
+}
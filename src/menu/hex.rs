@@ -0,0 +1,274 @@
+//! Hexagon shaped buttons, and the honeycomb grids they are laid out in.
+//!
+//! Hex buttons are drawn with the plain hex from the tile sheet, tinted with
+//! the button colors, and only react to the pointer inside of the hexagon
+//! rather than anywhere in their bounding box, so neighbouring cells in a
+//! honeycomb don't steal each other's clicks.
+use super::*;
+use crate::prelude::*;
+
+use bevy::{
+    ecs::{hierarchy::ChildSpawnerCommands, system::EntityCommands},
+    picking::{
+        PickSet,
+        hover::{HoverMap, generate_hovermap, update_interactions},
+    },
+    prelude::*,
+    ui::{FocusPolicy, UiSystem},
+};
+
+/// The index of the plain hex in the tile sheet, which is tinted for buttons.
+const HEX_BUTTON_TILE: usize = 0;
+/// How many tiles are in the tile sheet.
+const TILE_SHEET_COLUMNS: u32 = 14;
+/// How far apart rows of pointy topped hexes are, as a fraction of their height.
+const HEX_ROW_SPACING: f32 = 0.75;
+
+pub struct MenuHexPlugin;
+
+impl Plugin for MenuHexPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, init_resource::<HexButtonImage>)
+            .add_systems(
+                PreUpdate,
+                (
+                    hex_hover_filter
+                        .in_set(PickSet::Hover)
+                        .after(generate_hovermap)
+                        .before(update_interactions),
+                    hex_interaction.after(UiSystem::Focus).after(PickSet::Hover),
+                ),
+            )
+            .add_systems(PostUpdate, layout_hex_grids.before(UiSystem::Layout));
+    }
+}
+
+/// The image hex buttons are drawn with.
+#[derive(Resource)]
+pub struct HexButtonImage {
+    image: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+}
+
+impl FromWorld for HexButtonImage {
+    fn from_world(world: &mut World) -> Self {
+        let image = world.resource::<AssetServer>().load(TILE_ASSET_LOAD_PATH);
+        let layout =
+            world
+                .resource_mut::<Assets<TextureAtlasLayout>>()
+                .add(TextureAtlasLayout::from_grid(
+                    UVec2::new(TILE_SIZE.x as u32, TILE_SIZE.y as u32),
+                    TILE_SHEET_COLUMNS,
+                    1,
+                    None,
+                    None,
+                ));
+        Self { image, layout }
+    }
+}
+
+impl HexButtonImage {
+    fn to_node(&self, color: Color) -> ImageNode {
+        ImageNode::from_atlas_image(
+            self.image.clone(),
+            TextureAtlas {
+                layout: self.layout.clone(),
+                index: HEX_BUTTON_TILE,
+            },
+        )
+        .with_color(color)
+    }
+}
+
+/// Marks a button as hexagon shaped, so it is tinted instead of
+/// given a background, and is only picked inside of the hexagon.
+#[derive(Component, Default)]
+pub struct HexButton;
+
+/// Lays out its children with a [`HexCell`] in a honeycomb of pointy topped hexes,
+/// sizing itself to fit them.
+#[derive(Component, Debug, Clone, Copy)]
+#[require(Node)]
+pub struct HexGrid {
+    /// The width of each cell, with the height following the hex art's proportions.
+    pub cell_width: f32,
+    /// The space between neighbouring cells.
+    pub gap: f32,
+}
+
+impl HexGrid {
+    pub fn new(cell_width: f32) -> Self {
+        Self {
+            cell_width,
+            gap: 8.0,
+        }
+    }
+
+    fn cell_size(&self) -> Vec2 {
+        Vec2::new(self.cell_width, hex_height(self.cell_width))
+    }
+
+    /// The top left corner of `cell`, relative to that of the cell at the origin.
+    fn cell_offset(&self, cell: HexCell) -> Vec2 {
+        let size = self.cell_size();
+        Vec2::new(
+            (cell.q as f32 + cell.r as f32 / 2.0) * (size.x + self.gap),
+            cell.r as f32 * (size.y * HEX_ROW_SPACING + self.gap),
+        )
+    }
+}
+
+/// The position of a node in its [`HexGrid`], in axial coordinates
+/// where `r` is the row going down and `q` is the column going right.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexCell {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl HexCell {
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// The neighbouring cells in `direction`, most preferred first,
+    /// where `direction` has `y` going down like the UI.
+    ///
+    /// Up and down alternate between the two cells above or below depending on the row,
+    /// so that going up repeatedly heads straight up rather than drifting to one side.
+    pub fn toward(self, direction: Vec2) -> [Self; 2] {
+        let Self { q, r } = self;
+        let (west, east) = if direction.y < 0.0 {
+            (Self::new(q, r - 1), Self::new(q + 1, r - 1))
+        } else if direction.y > 0.0 {
+            (Self::new(q - 1, r + 1), Self::new(q, r + 1))
+        } else if direction.x < 0.0 {
+            return [Self::new(q - 1, r); 2];
+        } else {
+            return [Self::new(q + 1, r); 2];
+        };
+
+        if direction.x < 0.0 || (direction.x == 0.0 && r.rem_euclid(2) == 0) {
+            [west, east]
+        } else {
+            [east, west]
+        }
+    }
+}
+
+/// The height of a hex with the given width, keeping the proportions of the art.
+fn hex_height(width: f32) -> f32 {
+    width * TILE_SIZE.y / TILE_SIZE.x
+}
+
+/// Whether `position` is inside of a pointy topped hexagon filling the node,
+/// where `position` is normalized with `(0, 0)` being the top left corner
+/// and `(1, 1)` the bottom right.
+pub fn hex_contains(position: Vec2) -> bool {
+    let offset = (position - Vec2::splat(0.5)).abs();
+    offset.x <= 0.5 && offset.y + offset.x / 2.0 <= 0.5
+}
+
+/// Spawns a hex shaped button with centered text.
+pub fn hex_button<'a>(
+    builder: &'a mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    image: &HexButtonImage,
+    text: impl Into<Localized>,
+    width: f32,
+) -> EntityCommands<'a> {
+    builder.spawn((
+        Button,
+        HexButton,
+        Node {
+            width: Val::Px(width),
+            height: Val::Px(hex_height(width)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        image.to_node(style.button_color),
+        StyleRole::Button,
+        // Let the pointer through to the neighbouring cells when
+        // it is outside of the hexagon but inside of the node.
+        FocusPolicy::Pass,
+        Pickable {
+            should_block_lower: false,
+            is_hoverable: true,
+        },
+        children![(localized(text), text_style(style), Pickable::IGNORE)],
+    ))
+}
+
+/// Removes the hex buttons from the hover map when the pointer is outside of their hexagon,
+/// so they aren't hovered or clicked.
+fn hex_hover_filter(mut hover_map: ResMut<HoverMap>, hexes: Query<(), With<HexButton>>) {
+    for hits in hover_map.values_mut() {
+        hits.retain(|entity, hit| {
+            !hexes.contains(*entity)
+                // The UI picking backend gives the normalized position in the node.
+                || hit.position.is_some_and(|position| hex_contains(position.xy()))
+        });
+    }
+}
+
+/// Clears the [`Interaction`] of hex buttons that the pointer is only over the corners of,
+/// as it is worked out from the bounding box.
+fn hex_interaction(
+    hover_map: Res<HoverMap>,
+    mut hexes: Query<(Entity, &mut Interaction), With<HexButton>>,
+) {
+    for (entity, mut interaction) in hexes.iter_mut() {
+        let hovered = hover_map.values().any(|hits| hits.contains_key(&entity));
+        if !hovered {
+            interaction.set_if_neq(Interaction::None);
+        }
+    }
+}
+
+fn layout_hex_grids(
+    changed_grids: Query<Entity, Or<(Changed<HexGrid>, Changed<Children>)>>,
+    changed_cells: Query<&ChildOf, Changed<HexCell>>,
+    mut grids: Query<(&HexGrid, &mut Node, &Children), Without<HexCell>>,
+    mut cells: Query<(&HexCell, &mut Node)>,
+) {
+    let dirty = changed_grids
+        .iter()
+        .chain(changed_cells.iter().map(ChildOf::parent))
+        .collect::<Vec<_>>();
+
+    for grid_entity in dirty {
+        let Ok((grid, mut grid_node, children)) = grids.get_mut(grid_entity) else {
+            continue;
+        };
+        let children: &[Entity] = children;
+
+        let offsets = children
+            .iter()
+            .filter_map(|child| {
+                cells
+                    .get(*child)
+                    .ok()
+                    .map(|(cell, _)| grid.cell_offset(*cell))
+            })
+            .collect::<Vec<_>>();
+
+        let Some(min) = offsets.iter().copied().reduce(Vec2::min) else {
+            continue;
+        };
+        let max = offsets.iter().copied().fold(min, Vec2::max) + grid.cell_size();
+
+        grid_node.width = Val::Px(max.x - min.x);
+        grid_node.height = Val::Px(max.y - min.y);
+
+        for &child in children {
+            let Ok((cell, mut node)) = cells.get_mut(child) else {
+                continue;
+            };
+            let position = grid.cell_offset(*cell) - min;
+            node.position_type = PositionType::Absolute;
+            node.left = Val::Px(position.x);
+            node.top = Val::Px(position.y);
+        }
+    }
+}
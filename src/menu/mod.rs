@@ -1,8 +1,30 @@
-//! TODO: Make the UI hexagon based.
+mod accessibility;
+mod camera;
 mod controls;
-
+mod display;
+mod hex;
+mod navigation;
+mod pause;
+mod sky;
+mod sound;
+mod theme;
+mod widgets;
+
+use crate::locale::Language;
+use crate::newgame::{SavedGame, SessionSeeds, start_game};
 use crate::prelude::*;
+use crate::style::restyle;
+use accessibility::*;
+use camera::*;
 use controls::*;
+use display::*;
+use hex::*;
+use navigation::*;
+use pause::*;
+use sky::*;
+use sound::*;
+use theme::*;
+use widgets::*;
 
 use bevy::{input::mouse::MouseScrollUnit, prelude::*};
 
@@ -13,22 +35,34 @@ pub struct MenuPlugin;
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<MenuState>()
-            .add_systems(Update, (button_highlight).run_if(in_state(GameState::Menu)))
             .add_systems(
                 Update,
-                escape_out
-                    .run_if(in_state(GameState::Menu).and(not(in_state(MenuState::Controls)))),
+                button_highlight
+                    .after(restyle)
+                    .run_if(not(in_state(MenuState::Disabled))),
+            )
+            .add_systems(
+                Update,
+                escape_out.run_if(
+                    not(in_state(MenuState::Disabled)).and(not(in_state(MenuState::Controls))),
+                ),
             )
             .add_systems(OnEnter(GameState::Menu), menu_screen_enter)
             .add_systems(OnEnter(MenuState::Main), main_enter)
             .add_systems(OnExit(MenuState::Main), despawn_all_with::<OnMenuScreen>)
             .add_systems(OnEnter(MenuState::Settings), settings_enter)
             .add_systems(OnExit(MenuState::Settings), despawn_all_with::<OnSettings>)
-            .add_systems(OnEnter(MenuState::Display), display_enter)
-            .add_systems(OnExit(MenuState::Display), despawn_all_with::<OnDisplay>)
-            .add_systems(OnEnter(MenuState::Sound), sound_enter)
-            .add_systems(OnExit(MenuState::Sound), despawn_all_with::<OnSoundScreen>)
-            .add_plugins(MenuControlsPlugin);
+            .add_plugins(MenuAccessibilityPlugin)
+            .add_plugins(MenuCameraPlugin)
+            .add_plugins(MenuControlsPlugin)
+            .add_plugins(MenuHexPlugin)
+            .add_plugins(MenuNavigationPlugin)
+            .add_plugins(MenuPausePlugin)
+            .add_plugins(MenuSkyPlugin)
+            .add_plugins(MenuSoundPlugin)
+            .add_plugins(MenuThemePlugin)
+            .add_plugins(MenuDisplayPlugin)
+            .add_plugins(MenuWidgetsPlugin);
     }
 }
 
@@ -37,9 +71,14 @@ enum MenuState {
     #[default]
     Disabled,
     Main,
+    /// The pause menu, shown over the game.
+    Pause,
     Settings,
     Display,
     Sound,
+    Camera,
+    Sky,
+    Theme,
     Controls,
 }
 
@@ -49,26 +88,29 @@ struct PromptTarget(Control, usize);
 #[derive(Component)]
 struct OnMenuScreen;
 
-#[derive(Component)]
-struct OnSettings;
+/// The width of the hex buttons on the main and pause menus.
+const MAIN_MENU_HEX_WIDTH: f32 = 200.0;
 
 #[derive(Component)]
-struct OnDisplay;
-
-#[derive(Component)]
-struct OnSoundScreen;
+struct OnSettings;
 
 /// Specifies the action that should be taken the button it is on is clicked.
 ///
 /// The node will need to be observed by `menu_button_action` for this to take effect.
 #[derive(Component)]
 enum MenuButtonAction {
-    Play,
+    NewGame,
+    /// Continues the saved game.
+    Continue,
+    /// Goes to the top menu, which is the pause menu when in game.
     MainMenu,
     Settings,
     Controls,
     Display,
     Sound,
+    Camera,
+    Sky,
+    Theme,
     Quit,
 }
 
@@ -76,51 +118,100 @@ enum MenuButtonAction {
 #[derive(Component)]
 struct SelectedOption;
 
+/// Tag component used to mark a button whose setting conflicts with another
+#[derive(Component)]
+struct Conflicting;
+
 fn menu_screen_enter(mut menu_state: ResMut<NextState<MenuState>>) {
     menu_state.set(MenuState::Main);
 }
 
+/// The menu at the top of the settings, which is the pause menu when in game.
+fn top_menu(game_state: &GameState) -> MenuState {
+    match game_state {
+        GameState::Game => MenuState::Pause,
+        _ => MenuState::Main,
+    }
+}
+
 fn escape_out(
+    mut commands: Commands,
     menu_state: Res<State<MenuState>>,
+    game_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<MenuState>>,
     key: Res<ControlState>,
 ) {
     if key.just_pressed(Control::Pause) {
         use MenuState as M;
         match *menu_state.get() {
-            // TODO: Implement title screen and pausing separately.
             M::Disabled | M::Main => {}
-            M::Settings => next_state.set(MenuState::Main),
-            M::Sound | M::Display => next_state.set(MenuState::Settings),
+            M::Pause => resume_game(&mut commands),
+            M::Settings => next_state.set(top_menu(game_state.get())),
+            M::Sound | M::Display | M::Camera | M::Sky | M::Theme => {
+                next_state.set(MenuState::Settings)
+            }
             M::Controls => unreachable!(),
         }
     }
 }
 
+/// The color of a button, for its interaction and whether it is the
+/// [`SelectedOption`] or has a [`Conflicting`] binding.
+fn button_color(
+    style: &Style,
+    interaction: Interaction,
+    selected: bool,
+    conflicting: bool,
+) -> Color {
+    match (interaction, selected) {
+        (Interaction::Pressed, _) | (Interaction::None, true) => style.pressed_button_color,
+        (Interaction::Hovered, true) => style.hovered_pressed_button_color,
+        (Interaction::Hovered, false) => style.hovered_button_color,
+        (Interaction::None, false) if conflicting => style.conflict_button_color,
+        (Interaction::None, false) => style.button_color,
+    }
+}
+
+/// Colors buttons by their state, and recolors all of them when the [`Style`] changes.
 fn button_highlight(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, Option<&SelectedOption>),
-        (Changed<Interaction>, With<Button>),
+        (
+            Ref<Interaction>,
+            &mut BackgroundColor,
+            Option<&mut ImageNode>,
+            Option<&SelectedOption>,
+            Has<Conflicting>,
+            Has<HexButton>,
+        ),
+        With<Button>,
     >,
     style: Res<Style>,
 ) {
-    for (interaction, mut background_color, selected) in &mut interaction_query {
-        *background_color = match (*interaction, selected) {
-            (Interaction::Pressed, _) | (Interaction::None, Some(_)) => {
-                style.pressed_button_color.into()
-            }
-            (Interaction::Hovered, Some(_)) => style.hovered_pressed_button_color.into(),
-            (Interaction::Hovered, Option::None) => style.hovered_button_color.into(),
-            (Interaction::None, Option::None) => style.button_color.into(),
+    for (interaction, mut background_color, image, selected, conflicting, hex) in
+        &mut interaction_query
+    {
+        if !interaction.is_changed() && !style.is_changed() {
+            continue;
+        }
+
+        let color = button_color(&style, *interaction, selected.is_some(), conflicting);
+
+        // Hex buttons are tinted, as their background would fill the corners.
+        match image {
+            Some(mut image) if hex => image.color = color,
+            _ => *background_color = color.into(),
         }
     }
 }
 
 fn menu_button_click(
     mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
     mut app_exit_events: EventWriter<AppExit>,
     mut menu_state: ResMut<NextState<MenuState>>,
-    mut game_state: ResMut<NextState<GameState>>,
+    current_game_state: Res<State<GameState>>,
+    database: Res<Database>,
+    mut seeds: ResMut<SessionSeeds>,
     target_query: Query<&MenuButtonAction>,
 ) {
     if click.button == PointerButton::Primary {
@@ -131,267 +222,129 @@ fn menu_button_click(
             MenuButtonAction::Quit => {
                 app_exit_events.write(AppExit::Success);
             }
-            MenuButtonAction::Play => {
+            MenuButtonAction::NewGame => {
                 menu_state.set(MenuState::Disabled);
-                game_state.set(GameState::Game);
+                start_game(&mut commands, seeds.new_session());
+            }
+            MenuButtonAction::Continue => {
+                if let Some(saved) = SavedGame::load(&database) {
+                    menu_state.set(MenuState::Disabled);
+                    start_game(&mut commands, saved.into());
+                } else {
+                    warn!("There is no saved game to continue");
+                }
             }
             MenuButtonAction::Settings => menu_state.set(MenuState::Settings),
             MenuButtonAction::Controls => menu_state.set(MenuState::Controls),
             MenuButtonAction::Display => menu_state.set(MenuState::Display),
             MenuButtonAction::Sound => menu_state.set(MenuState::Sound),
-            MenuButtonAction::MainMenu => menu_state.set(MenuState::Main),
+            MenuButtonAction::Camera => menu_state.set(MenuState::Camera),
+            MenuButtonAction::Sky => menu_state.set(MenuState::Sky),
+            MenuButtonAction::Theme => menu_state.set(MenuState::Theme),
+            MenuButtonAction::MainMenu => menu_state.set(top_menu(current_game_state.get())),
         }
     }
 
     click.propagate(false);
 }
 
-fn main_enter(mut commands: Commands, style: Res<Style>) {
-    // Common style for all buttons on the screen
-    let button_node = Node {
-        width: Val::Px(300.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
-        justify_content: JustifyContent::Center,
-        align_items: AlignItems::Center,
-        ..default()
-    };
-    let button_text_font = style.font(33.0);
-
-    commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            OnMenuScreen,
-        ))
-        .with_children(|builder| {
-            builder
-                .spawn(Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    ..default()
-                })
-                .with_children(|builder| {
-                    // Display the game name
-                    builder.spawn((
-                        Text::new("A Hex Befalls\nThe Hexagons"),
-                        style.font(67.0),
-                        TextColor(style.title_color),
-                        Node {
-                            margin: UiRect::all(Val::Px(50.0)),
-                            ..default()
-                        },
-                    ));
-                    // Display three buttons for each action available from the main menu:
-                    // - new game
-                    // - settings
-                    // - quit
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(style.button_color),
-                            MenuButtonAction::Play,
-                            children![
-                                //(ImageNode::new(right_icon), button_icon_node.clone()),
-                                (
-                                    Text::new("New Game"),
-                                    button_text_font.clone(),
-                                    TextColor(style.text_color),
-                                    Pickable::IGNORE
-                                ),
-                            ],
-                        ))
-                        .observe(menu_button_click);
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(style.button_color),
-                            MenuButtonAction::Settings,
-                            children![
-                                //(ImageNode::new(wrench_icon), button_icon_node.clone()),
-                                (
-                                    Text::new("Settings"),
-                                    button_text_font.clone(),
-                                    TextColor(style.text_color),
-                                    Pickable::IGNORE
-                                ),
-                            ],
-                        ))
-                        .observe(menu_button_click);
-                    builder
-                        .spawn((
-                            Button,
-                            button_node,
-                            BackgroundColor(style.button_color),
-                            MenuButtonAction::Quit,
-                            children![
-                                //(ImageNode::new(exit_icon), button_icon_node),
-                                (
-                                    Text::new("Quit"),
-                                    button_text_font,
-                                    TextColor(style.text_color),
-                                    Pickable::IGNORE
-                                ),
-                            ],
-                        ))
-                        .observe(menu_button_click);
-                });
-        });
-}
-
-fn settings_enter(mut commands: Commands, style: Res<Style>) {
-    let button_node = Node {
-        width: Val::Px(200.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
-        justify_content: JustifyContent::Center,
-        align_items: AlignItems::Center,
-        ..default()
-    };
-
-    let button_text_style = (style.font(33.0), TextColor(style.text_color));
+fn main_enter(
+    mut commands: Commands,
+    style: Res<Style>,
+    hex_image: Res<HexButtonImage>,
+    database: Res<Database>,
+) {
+    let has_saved_game = SavedGame::load(&database).is_some();
 
     commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            OnSettings,
-        ))
+        .spawn(menu_root(OnMenuScreen))
         .with_children(|builder| {
-            builder
-                .spawn(Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    ..default()
-                })
-                .with_children(|builder| {
-                    [
-                        (MenuButtonAction::Controls, "Controls"),
-                        (MenuButtonAction::Display, "Display"),
-                        (MenuButtonAction::Sound, "Sound"),
-                        (MenuButtonAction::MainMenu, "Back"),
-                    ]
-                    .into_iter()
-                    .for_each(|(action, text)| {
-                        builder
-                            .spawn((
-                                Button,
-                                button_node.clone(),
-                                BackgroundColor(style.button_color),
-                                action,
-                                children![(
-                                    Text::new(text),
-                                    button_text_style.clone(),
-                                    Pickable::IGNORE
-                                )],
-                            ))
-                            .observe(menu_button_click);
+            builder.spawn(menu_column()).with_children(|builder| {
+                // Display the game name
+                builder.spawn((
+                    localized("menu.title"),
+                    style.text(StyleRole::Title, TextSize::Title),
+                    Node {
+                        margin: UiRect::all(Val::Px(50.0)),
+                        ..default()
+                    },
+                ));
+                // Display a honeycomb of buttons for each action available from the main menu:
+                // - new game, and continue if there is a saved game, on top
+                // - settings and quit below
+                builder
+                    .spawn(HexGrid::new(MAIN_MENU_HEX_WIDTH))
+                    .with_children(|builder| {
+                        [
+                            (
+                                MenuButtonAction::NewGame,
+                                "menu.new_game",
+                                HexCell::new(0, 0),
+                            ),
+                            (
+                                MenuButtonAction::Continue,
+                                "menu.continue",
+                                HexCell::new(1, 0),
+                            ),
+                            (
+                                MenuButtonAction::Settings,
+                                "menu.settings",
+                                HexCell::new(-1, 1),
+                            ),
+                            (MenuButtonAction::Quit, "menu.quit", HexCell::new(0, 1)),
+                        ]
+                        .into_iter()
+                        .filter(|(action, _, _)| {
+                            has_saved_game || !matches!(action, MenuButtonAction::Continue)
+                        })
+                        .for_each(|(action, text, cell)| {
+                            hex_button(builder, &style, &hex_image, text, MAIN_MENU_HEX_WIDTH)
+                                .insert((action, cell))
+                                .observe(menu_button_click);
+                        });
                     });
-                });
+            });
         });
 }
 
-fn display_enter(mut commands: Commands, style: Res<Style>) {
-    let button_node = Node {
-        width: Val::Px(200.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
-        justify_content: JustifyContent::Center,
-        align_items: AlignItems::Center,
-        ..default()
-    };
-
-    let button_text_style = (style.font(33.0), TextColor(style.text_color));
-
+fn settings_enter(mut commands: Commands, style: Res<Style>, language: Res<Language>) {
     commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            OnDisplay,
-        ))
+        .spawn(menu_root(OnSettings))
         .with_children(|builder| {
-            builder
-                .spawn(Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    ..default()
-                })
-                .with_children(|builder| {
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(style.button_color),
-                            MenuButtonAction::Settings,
-                            children![(Text::new("Back"), button_text_style.clone())],
-                        ))
-                        .observe(menu_button_click);
+            builder.spawn(menu_column()).with_children(|builder| {
+                setting_row(builder, &style, "settings.language", |builder| {
+                    cycle_select(
+                        builder,
+                        &style,
+                        CycleSelect::new(
+                            Language::ALL
+                                .map(|language| Localized::verbatim(language.native_name())),
+                            option_index(&Language::ALL, &language),
+                        ),
+                    )
+                    .observe(
+                        |changed: Trigger<SelectChanged>, mut language: ResMut<Language>| {
+                            *language = Language::ALL[changed.0];
+                        },
+                    );
                 });
-        });
-}
-
-fn sound_enter(mut commands: Commands, style: Res<Style> /*volume: Res<Volume>*/) {
-    let button_node = Node {
-        width: Val::Px(200.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
-        justify_content: JustifyContent::Center,
-        align_items: AlignItems::Center,
-        ..default()
-    };
-    let button_text_style = (
-        style.font(33.0),
-        TextLayout::new_with_justify(JustifyText::Center),
-        TextColor(style.text_color),
-    );
 
-    //let button_node_clone = button_node.clone();
-    commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            OnSoundScreen,
-        ))
-        .with_children(|builder| {
-            builder
-                .spawn(Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    ..default()
-                })
-                .with_children(|builder| {
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(style.button_color),
-                            MenuButtonAction::Settings,
-                            children![(Text::new("Back"), button_text_style.clone())],
-                        ))
+                [
+                    (MenuButtonAction::Controls, "menu.controls"),
+                    (MenuButtonAction::Display, "menu.display"),
+                    (MenuButtonAction::Sound, "menu.sound"),
+                    (MenuButtonAction::Camera, "menu.camera"),
+                    (MenuButtonAction::Sky, "menu.sky"),
+                    (MenuButtonAction::Theme, "menu.theme"),
+                    (MenuButtonAction::MainMenu, "menu.back"),
+                ]
+                .into_iter()
+                .for_each(|(action, text)| {
+                    button(builder, &style, text, 200.0)
+                        .insert(action)
                         .observe(menu_button_click);
                 });
+            });
         });
 }
 
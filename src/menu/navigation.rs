@@ -0,0 +1,335 @@
+//! Directional focus navigation for the menus, so they can be used without a mouse.
+use super::*;
+use crate::prelude::*;
+use crate::sound::{PlaySound, SoundEffect};
+
+use bevy::{
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerId},
+    },
+    prelude::*,
+    render::camera::NormalizedRenderTarget,
+    window::{PrimaryWindow, WindowRef},
+};
+use core::time::Duration;
+
+/// The width of the ring drawn around the focused node.
+const FOCUS_RING_WIDTH: f32 = 4.0;
+/// How much the distance off of the direction of travel counts against a node.
+const FOCUS_PERPENDICULAR_WEIGHT: f32 = 2.0;
+
+pub struct MenuNavigationPlugin;
+
+impl Plugin for MenuNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuFocus>().add_systems(
+            Update,
+            (
+                hide_focus_on_mouse,
+                navigate_focus,
+                confirm_focus,
+                // Hex buttons are tinted over their highlight.
+                update_focus_ring.after(button_highlight),
+                scroll_to_focus,
+            )
+                .chain()
+                .run_if(
+                    not(in_state(MenuState::Disabled)).and(not(in_state(ControlsState::Prompt))),
+                ),
+        );
+    }
+}
+
+/// The node that currently has focus in the menu.
+#[derive(Resource, Default, Debug)]
+pub struct MenuFocus {
+    pub entity: Option<Entity>,
+    /// Whether the focus should be shown and act on `Select`.
+    /// This is turned off when the mouse is used, and on when
+    /// the user navigates.
+    pub visible: bool,
+}
+
+/// Restricts navigation to the focusable nodes in this node, such as for a dialog.
+#[derive(Component)]
+pub struct FocusTrap;
+
+/// Marks nodes that use left and right themselves while focused, such as sliders,
+/// so only up and down move the focus.
+#[derive(Component, Default)]
+pub struct HorizontalInput;
+
+/// Marks the node currently showing the focus ring.
+#[derive(Component)]
+struct FocusRing;
+
+fn hide_focus_on_mouse(
+    mut focus: ResMut<MenuFocus>,
+    mut cursor: EventReader<CursorMoved>,
+    mouse: Res<ButtonInput<MouseButton>>,
+) {
+    let moved = cursor.read().count() > 0;
+    if (moved || mouse.get_just_pressed().len() > 0) && focus.visible {
+        focus.visible = false;
+    }
+}
+
+/// Returns the focusable nodes, only including those in the
+/// [`FocusTrap`] if there is one.
+fn focus_candidates(
+    buttons: &Query<(Entity, &GlobalTransform, &InheritedVisibility), With<Button>>,
+    traps: &Query<Entity, With<FocusTrap>>,
+    parents: &Query<&ChildOf>,
+) -> Vec<(Entity, Vec2)> {
+    let trap = traps.iter().next();
+
+    buttons
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .filter(|(entity, _, _)| match trap {
+            Some(trap) => parents.iter_ancestors(*entity).any(|e| e == trap),
+            None => true,
+        })
+        .map(|(entity, transform, _)| (entity, transform.translation().xy()))
+        .collect()
+}
+
+fn navigate_focus(
+    mut focus: ResMut<MenuFocus>,
+    mut sounds: EventWriter<PlaySound>,
+    input: Res<ControlState>,
+    buttons: Query<(Entity, &GlobalTransform, &InheritedVisibility), With<Button>>,
+    traps: Query<Entity, With<FocusTrap>>,
+    parents: Query<&ChildOf>,
+    horizontal: Query<(), With<HorizontalInput>>,
+    cells: Query<(&HexCell, &ChildOf)>,
+) {
+    let captures_horizontal = focus.visible
+        && focus
+            .entity
+            .is_some_and(|entity| horizontal.contains(entity));
+
+    // UI space has `y` going down.
+    let direction = [
+        (Control::MoveUp, Vec2::NEG_Y),
+        (Control::MoveDown, Vec2::Y),
+        (Control::MoveLeft, Vec2::NEG_X),
+        (Control::MoveRight, Vec2::X),
+    ]
+    .into_iter()
+    .filter(|(control, _)| input.just_pressed(*control))
+    .map(|(_, direction)| direction)
+    .filter(|direction| !(captures_horizontal && direction.y == 0.0))
+    .sum::<Vec2>();
+
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    let candidates = focus_candidates(&buttons, &traps, &parents);
+
+    let current = focus
+        .entity
+        .and_then(|focused| candidates.iter().find(|(entity, _)| *entity == focused))
+        .copied();
+
+    focus.visible = true;
+
+    let Some((current, position)) = current else {
+        // Start from the top left most node when nothing is focused.
+        focus.entity = candidates
+            .iter()
+            .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+            .map(|(entity, _)| *entity);
+        return;
+    };
+
+    let direction = direction.normalize();
+
+    // Move between neighbouring cells in a honeycomb, only falling back
+    // to the closest node when leaving it.
+    let honeycomb = cells.get(current).ok().and_then(|(cell, grid)| {
+        cell.toward(direction).into_iter().find_map(|target| {
+            candidates.iter().map(|(entity, _)| *entity).find(|entity| {
+                cells.get(*entity).is_ok_and(|(other, other_grid)| {
+                    *other == target && other_grid.parent() == grid.parent()
+                })
+            })
+        })
+    });
+
+    let next = honeycomb.or_else(|| {
+        candidates
+            .iter()
+            .filter(|(entity, _)| *entity != current)
+            .filter_map(|(entity, other)| {
+                let offset = *other - position;
+                let along = offset.dot(direction);
+                (along > 0.0).then(|| {
+                    let across = offset.perp_dot(direction).abs();
+                    (*entity, along + across * FOCUS_PERPENDICULAR_WEIGHT)
+                })
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, _)| entity)
+    });
+
+    if let Some(next) = next {
+        focus.entity = Some(next);
+        sounds.write(PlaySound(SoundEffect::UiMove));
+    }
+}
+
+/// Clicks the focused node when `Select` is released,
+/// mirroring a mouse click.
+fn confirm_focus(
+    mut commands: Commands,
+    mut sounds: EventWriter<PlaySound>,
+    focus: Res<MenuFocus>,
+    input: Res<ControlState>,
+    transforms: Query<&GlobalTransform>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    camera: Query<Entity, With<Camera>>,
+) {
+    if !focus.visible || !input.just_released(Control::Select) {
+        return;
+    }
+
+    let Some(target) = focus.entity else {
+        return;
+    };
+
+    if click_entity(&mut commands, target, &transforms, &window, &camera) {
+        sounds.write(PlaySound(SoundEffect::UiClick));
+    }
+}
+
+/// Triggers a primary click on `target` as if the mouse clicked its center,
+/// returning whether it could be clicked.
+pub(super) fn click_entity(
+    commands: &mut Commands,
+    target: Entity,
+    transforms: &Query<&GlobalTransform>,
+    window: &Query<Entity, With<PrimaryWindow>>,
+    camera: &Query<Entity, With<Camera>>,
+) -> bool {
+    let (Ok(transform), Some(window), Some(camera)) = (
+        transforms.get(target),
+        WindowRef::Primary.normalize(window.single().ok()),
+        camera.iter().next(),
+    ) else {
+        return false;
+    };
+
+    let location = Location {
+        target: NormalizedRenderTarget::Window(window),
+        position: transform.translation().xy(),
+    };
+
+    commands.trigger_targets(
+        Pointer::new(
+            PointerId::Mouse,
+            location,
+            target,
+            Click {
+                button: PointerButton::Primary,
+                hit: HitData::new(camera, 0.0, None, None),
+                duration: Duration::ZERO,
+            },
+        ),
+        target,
+    );
+    true
+}
+
+fn update_focus_ring(
+    mut commands: Commands,
+    focus: Res<MenuFocus>,
+    style: Res<Style>,
+    rings: Query<Entity, With<FocusRing>>,
+    mut hexes: Query<
+        (
+            &mut ImageNode,
+            &Interaction,
+            Has<SelectedOption>,
+            Has<Conflicting>,
+        ),
+        With<HexButton>,
+    >,
+) {
+    if !focus.is_changed() && !style.is_changed() {
+        return;
+    }
+
+    for entity in rings.iter() {
+        if Some(entity) != focus.entity || !focus.visible {
+            commands.entity(entity).remove::<(FocusRing, Outline)>();
+            if let Ok((mut image, interaction, selected, conflicting)) = hexes.get_mut(entity) {
+                image.color = button_color(&style, *interaction, selected, conflicting);
+            }
+        }
+    }
+
+    if let Some(entity) = focus.entity.filter(|_| focus.visible) {
+        // A rectangular ring would look out of place around a hexagon, so they are tinted instead.
+        if let Ok((mut image, ..)) = hexes.get_mut(entity) {
+            image.color = style.hovered_button_color;
+            commands.entity(entity).insert(FocusRing);
+        } else if let Ok(mut entity) = commands.get_entity(entity) {
+            entity.insert((
+                FocusRing,
+                Outline::new(
+                    Val::Px(FOCUS_RING_WIDTH),
+                    Val::Px(FOCUS_RING_WIDTH / 2.0),
+                    style.pressed_button_color,
+                ),
+            ));
+        }
+    }
+}
+
+/// Scrolls the closest scrolling ancestor of the focused node so that it is visible.
+fn scroll_to_focus(
+    focus: Res<MenuFocus>,
+    nodes: Query<(&ComputedNode, &GlobalTransform)>,
+    mut scrolled: Query<(&Node, &mut ScrollPosition)>,
+    parents: Query<&ChildOf>,
+) {
+    if !focus.is_changed() {
+        return;
+    }
+
+    let Some(focused) = focus.entity else {
+        return;
+    };
+
+    let Some(container) = parents.iter_ancestors(focused).find(
+        |e| matches!(scrolled.get(*e), Ok((node, _)) if node.overflow.y == OverflowAxis::Scroll),
+    ) else {
+        return;
+    };
+
+    let (Ok((focused_node, focused_transform)), Ok((container_node, container_transform))) =
+        (nodes.get(focused), nodes.get(container))
+    else {
+        return;
+    };
+
+    let Ok((_, mut scroll)) = scrolled.get_mut(container) else {
+        return;
+    };
+
+    // Both of these are in physical pixels, while the scroll position is logical.
+    let focused_top = focused_transform.translation().y - focused_node.size().y / 2.0;
+    let focused_bottom = focused_transform.translation().y + focused_node.size().y / 2.0;
+    let container_top = container_transform.translation().y - container_node.size().y / 2.0;
+    let container_bottom = container_transform.translation().y + container_node.size().y / 2.0;
+
+    if focused_top < container_top {
+        scroll.offset_y -= (container_top - focused_top) * container_node.inverse_scale_factor();
+    } else if focused_bottom > container_bottom {
+        scroll.offset_y +=
+            (focused_bottom - container_bottom) * container_node.inverse_scale_factor();
+    }
+}
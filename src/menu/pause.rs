@@ -0,0 +1,160 @@
+use super::*;
+use crate::day_night::GameClock;
+use crate::newgame::GameSession;
+use crate::prelude::*;
+
+use bevy::prelude::*;
+
+pub struct MenuPausePlugin;
+
+impl Plugin for MenuPausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, pause_game.run_if(in_state(PauseState::Running)))
+            .add_systems(OnEnter(PauseState::Paused), pause_time)
+            .add_systems(OnExit(PauseState::Paused), unpause_time)
+            .add_systems(OnEnter(MenuState::Pause), pause_enter)
+            .add_systems(OnExit(MenuState::Pause), despawn_all_with::<OnPauseScreen>);
+    }
+}
+
+#[derive(Component)]
+struct OnPauseScreen;
+
+/// The text telling the player whether saving worked.
+#[derive(Component)]
+struct SaveStatus;
+
+#[derive(Component)]
+enum PauseButtonAction {
+    Resume,
+    Save,
+    QuitToTitle,
+}
+
+fn pause_game(mut commands: Commands, input: Res<ControlState>) {
+    if input.just_pressed(Control::Pause) {
+        commands.set_state(PauseState::Paused);
+        commands.set_state(MenuState::Pause);
+    }
+}
+
+pub(super) fn resume_game(commands: &mut Commands) {
+    commands.set_state(PauseState::Running);
+    commands.set_state(MenuState::Disabled);
+}
+
+/// Stops game time, so everything using [`Time`] stands still.
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn pause_enter(mut commands: Commands, style: Res<Style>, hex_image: Res<HexButtonImage>) {
+    commands
+        .spawn((
+            menu_root(OnPauseScreen),
+            // Dim the game behind the menu.
+            style.background(StyleRole::Background),
+        ))
+        .with_children(|builder| {
+            builder.spawn(menu_column()).with_children(|builder| {
+                builder.spawn((
+                    localized("pause.title"),
+                    style.text(StyleRole::Text, TextSize::Title),
+                    Node {
+                        margin: UiRect::all(Val::Px(30.0)),
+                        ..default()
+                    },
+                ));
+
+                builder
+                    .spawn(HexGrid::new(MAIN_MENU_HEX_WIDTH))
+                    .with_children(|builder| {
+                        hex_button(
+                            builder,
+                            &style,
+                            &hex_image,
+                            "pause.resume",
+                            MAIN_MENU_HEX_WIDTH,
+                        )
+                        .insert((PauseButtonAction::Resume, HexCell::new(0, 0)))
+                        .observe(pause_button_click);
+                        hex_button(
+                            builder,
+                            &style,
+                            &hex_image,
+                            "pause.save",
+                            MAIN_MENU_HEX_WIDTH,
+                        )
+                        .insert((PauseButtonAction::Save, HexCell::new(1, 0)))
+                        .observe(pause_button_click);
+                        hex_button(
+                            builder,
+                            &style,
+                            &hex_image,
+                            "menu.settings",
+                            MAIN_MENU_HEX_WIDTH,
+                        )
+                        .insert((MenuButtonAction::Settings, HexCell::new(-1, 1)))
+                        .observe(menu_button_click);
+                        hex_button(
+                            builder,
+                            &style,
+                            &hex_image,
+                            "pause.to_title",
+                            MAIN_MENU_HEX_WIDTH,
+                        )
+                        .insert((PauseButtonAction::QuitToTitle, HexCell::new(0, 1)))
+                        .observe(pause_button_click);
+                    });
+
+                builder.spawn((
+                    SaveStatus,
+                    localized(Localized::default()),
+                    style.text(StyleRole::Text, TextSize::Caption),
+                ));
+            });
+        });
+}
+
+fn pause_button_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    database: Res<Database>,
+    session: Res<GameSession>,
+    clock: Res<GameClock>,
+    target_query: Query<&PauseButtonAction>,
+    mut status: Query<&mut Localized, With<SaveStatus>>,
+) {
+    if click.button == PointerButton::Primary {
+        let Ok(action) = target_query.get(click.target()) else {
+            return;
+        };
+
+        match action {
+            PauseButtonAction::Resume => resume_game(&mut commands),
+            PauseButtonAction::Save => {
+                let message = match session.to_saved(&clock).save(&database) {
+                    Ok(()) => Localized::new("pause.saved"),
+                    Err(err) => {
+                        warn!("Failed to save the game with: {err}");
+                        Localized::new("pause.save_failed")
+                            .with("error", Localized::verbatim(err.to_string()))
+                    }
+                };
+
+                for mut text in status.iter_mut() {
+                    *text = message.clone();
+                }
+            }
+            PauseButtonAction::QuitToTitle => {
+                commands.set_state(GameState::Menu);
+            }
+        }
+    }
+
+    click.propagate(false);
+}
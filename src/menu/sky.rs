@@ -0,0 +1,31 @@
+use super::*;
+use crate::prelude::*;
+use crate::sky::SkySettings;
+
+use bevy::prelude::*;
+
+pub struct MenuSkyPlugin;
+
+impl Plugin for MenuSkyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Sky), sky_enter)
+            .add_systems(OnExit(MenuState::Sky), despawn_all_with::<OnSkyScreen>);
+    }
+}
+
+#[derive(Component)]
+struct OnSkyScreen;
+
+fn sky_enter(mut commands: Commands, style: Res<Style>, settings: Res<SkySettings>) {
+    commands
+        .spawn(menu_root(OnSkyScreen))
+        .with_children(|builder| {
+            builder.spawn(menu_column()).with_children(|builder| {
+                settings_rows(builder, &style, &*settings);
+
+                button(builder, &style, "menu.back", 200.0)
+                    .insert(MenuButtonAction::Settings)
+                    .observe(menu_button_click);
+            });
+        });
+}
@@ -0,0 +1,56 @@
+use super::*;
+use crate::prelude::*;
+use crate::sound::{AudioChannel, SoundSettings};
+
+use bevy::prelude::*;
+
+/// How much the volume changes by with the keyboard or gamepad.
+const VOLUME_STEP: f32 = 0.05;
+
+pub struct MenuSoundPlugin;
+
+impl Plugin for MenuSoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Sound), sound_enter)
+            .add_systems(OnExit(MenuState::Sound), despawn_all_with::<OnSoundScreen>);
+    }
+}
+
+#[derive(Component)]
+struct OnSoundScreen;
+
+fn sound_enter(mut commands: Commands, style: Res<Style>, settings: Res<SoundSettings>) {
+    commands
+        .spawn(menu_root(OnSoundScreen))
+        .with_children(|builder| {
+            builder.spawn(menu_column()).with_children(|builder| {
+                for channel in AudioChannel::ALL {
+                    setting_row(builder, &style, channel, |builder| {
+                        slider(
+                            builder,
+                            &style,
+                            Slider::new(settings.get(channel), 0.0, 1.0, VOLUME_STEP),
+                        )
+                        .observe(
+                            move |changed: Trigger<SliderChanged>,
+                                  mut settings: ResMut<SoundSettings>| {
+                                *settings.get_mut(channel) = changed.0;
+                            },
+                        );
+                    });
+                }
+
+                setting_row(builder, &style, "sound.mute_unfocused", |builder| {
+                    checkbox(builder, &style, settings.mute_on_focus_loss).observe(
+                        |changed: Trigger<CheckboxChanged>, mut settings: ResMut<SoundSettings>| {
+                            settings.mute_on_focus_loss = changed.0;
+                        },
+                    );
+                });
+
+                button(builder, &style, "menu.back", 200.0)
+                    .insert(MenuButtonAction::Settings)
+                    .observe(menu_button_click);
+            });
+        });
+}
@@ -0,0 +1,325 @@
+//! The theme editor, where the player picks a preset or edits each color of the [`Style`],
+//! and picks the font of text.
+use super::*;
+use crate::prelude::*;
+use crate::theme::{Theme, ThemeColor, ThemePresets, color_to_hex};
+
+use bevy::{ecs::hierarchy::ChildSpawnerCommands, prelude::*};
+
+/// Enough for `#RRGGBBAA`.
+const HEX_COLOR_MAX_LEN: usize = 9;
+const SWATCH_SIZE: f32 = 32.0;
+const COLOR_LABEL_WIDTH: f32 = 260.0;
+const PREVIEW_BUTTON_WIDTH: f32 = 160.0;
+const FONT_PATH_MAX_LEN: usize = 260;
+
+pub struct MenuThemePlugin;
+
+impl Plugin for MenuThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Theme), theme_enter)
+            .add_systems(OnExit(MenuState::Theme), despawn_all_with::<OnTheme>)
+            .add_systems(
+                Update,
+                (
+                    (update_theme_preview, update_preset_dropdown)
+                        .run_if(resource_changed::<Style>),
+                    reset_color_fields,
+                )
+                    .run_if(in_state(MenuState::Theme)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct OnTheme;
+
+/// The dropdown of preset themes.
+#[derive(Component)]
+struct PresetDropdown;
+
+/// A field editing a color of the [`Style`] as hex.
+#[derive(Component)]
+struct ColorField(ThemeColor);
+
+/// Explains why the font couldn't be loaded.
+#[derive(Component)]
+struct FontStatus;
+
+/// A node showing a color of the [`Style`] in its background.
+#[derive(Component)]
+struct ColorSwatch(ThemeColor);
+
+#[derive(Component)]
+enum ThemeButtonAction {
+    ResetToDefault,
+}
+
+/// The options of the preset dropdown, with "Custom" on the end when the colors match none.
+fn preset_dropdown(presets: &ThemePresets, theme: &Theme) -> Dropdown {
+    let mut options = presets
+        .0
+        .iter()
+        .map(|preset| Localized::verbatim(preset.name.clone()))
+        .collect::<Vec<_>>();
+
+    let selected = presets.find(theme).unwrap_or_else(|| {
+        options.push(Localized::new("theme.custom"));
+        options.len() - 1
+    });
+
+    Dropdown::new(options, selected)
+}
+
+fn theme_enter(mut commands: Commands, style: Res<Style>, presets: Res<ThemePresets>) {
+    let theme = style.theme(&presets);
+
+    commands.spawn(menu_root(OnTheme)).with_children(|builder| {
+        builder.spawn(menu_column()).with_children(|builder| {
+            setting_row(builder, &style, "theme.preset", |builder| {
+                dropdown(builder, &style, preset_dropdown(&presets, &theme))
+                    .insert(PresetDropdown)
+                    .observe(
+                        |changed: Trigger<SelectChanged>,
+                         presets: Res<ThemePresets>,
+                         mut style: ResMut<Style>| {
+                            // The last option is "Custom" when the colors match no preset.
+                            if let Some(preset) = presets.0.get(changed.0) {
+                                style.set_theme(preset);
+                            }
+                        },
+                    );
+            });
+
+            setting_row(builder, &style, "theme.font_file", |builder| {
+                text_field(
+                    builder,
+                    &style,
+                    TextField::new(font_field_text(&style), FONT_PATH_MAX_LEN)
+                        .with_placeholder("theme.default_font"),
+                )
+                .observe(font_field_submitted);
+            });
+            builder.spawn((
+                FontStatus,
+                localized(Localized::default()),
+                style.text(StyleRole::Warning, TextSize::Caption),
+            ));
+
+            // Sample buttons in each state, to see the colors together.
+            builder
+                .spawn(Node {
+                    margin: UiRect::vertical(Val::Px(10.0)),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    for (color, text) in [
+                        (ThemeColor::Button, "theme.preview.normal"),
+                        (ThemeColor::HoveredButton, "theme.preview.hovered"),
+                        (ThemeColor::PressedButton, "theme.preview.pressed"),
+                        (ThemeColor::ConflictButton, "theme.preview.conflict"),
+                    ] {
+                        builder.spawn((
+                            ColorSwatch(color),
+                            Node {
+                                margin: UiRect::all(Val::Px(5.0)),
+                                ..button_node(PREVIEW_BUTTON_WIDTH)
+                            },
+                            BackgroundColor(style.color(color)),
+                            children![(localized(text), text_style(&style), Pickable::IGNORE,)],
+                        ));
+                    }
+                });
+
+            scroll_list(builder, Val::Px(400.0)).with_children(|builder| {
+                for color in ThemeColor::ALL {
+                    color_row(builder, &style, color);
+                }
+            });
+
+            builder.spawn(Node::default()).with_children(|builder| {
+                button(builder, &style, "theme.reset", 300.0)
+                    .insert(ThemeButtonAction::ResetToDefault)
+                    .observe(theme_button_click);
+                button(builder, &style, "menu.back", 200.0)
+                    .insert(MenuButtonAction::Settings)
+                    .observe(menu_button_click);
+            });
+        });
+    });
+}
+
+/// Spawns a row with a swatch, the name of the color, and a field to type it in hex.
+fn color_row(builder: &mut ChildSpawnerCommands, style: &Style, color: ThemeColor) {
+    builder
+        .spawn((
+            SettingRow(color.into()),
+            Node {
+                align_items: AlignItems::Center,
+                margin: UiRect::vertical(Val::Px(5.0)),
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                ColorSwatch(color),
+                Node {
+                    width: Val::Px(SWATCH_SIZE),
+                    height: Val::Px(SWATCH_SIZE),
+                    margin: UiRect::right(Val::Px(8.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(style.color(color)),
+                BorderColor(style.text_color),
+                StyleRole::Text,
+            ));
+            builder.spawn((
+                localized(color),
+                text_style(style),
+                Node {
+                    width: Val::Px(COLOR_LABEL_WIDTH),
+                    ..default()
+                },
+            ));
+            text_field(
+                builder,
+                style,
+                TextField::new(color_to_hex(style.color(color)), HEX_COLOR_MAX_LEN)
+                    .with_placeholder(Localized::verbatim("#RRGGBB")),
+            )
+            .insert(ColorField(color))
+            .observe(color_field_changed)
+            .observe(color_field_submitted);
+        });
+}
+
+/// The path of the custom font, or nothing for the default.
+fn font_field_text(style: &Style) -> String {
+    style
+        .custom_font
+        .as_ref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_default()
+}
+
+/// Loads the font at the typed path, or goes back to the default font when it is left empty.
+fn font_field_submitted(
+    submitted: Trigger<TextSubmitted>,
+    asset_server: Res<AssetServer>,
+    mut style: ResMut<Style>,
+    mut fields: Query<&mut TextField>,
+    mut status: Query<&mut Localized, With<FontStatus>>,
+) {
+    let path = submitted.0.trim();
+    let result = if path.is_empty() {
+        if style.custom_font.is_some() {
+            style.reset_font(&asset_server);
+        }
+        Ok(())
+    } else {
+        style.set_custom_font(path.into(), &asset_server)
+    };
+
+    let message = match result {
+        Ok(()) => Localized::default(),
+        Err(err) => {
+            warn!("Failed to load the font {path} with: {err}");
+            // Show the font that is still in use.
+            if let Ok(mut field) = fields.get_mut(submitted.target()) {
+                field.value = font_field_text(&style);
+            }
+            Localized::from(&err)
+        }
+    };
+
+    for mut text in status.iter_mut() {
+        *text = message.clone();
+    }
+}
+
+/// Previews the color while it is typed, as soon as it is valid.
+fn color_field_changed(
+    changed: Trigger<TextChanged>,
+    fields: Query<&ColorField>,
+    mut style: ResMut<Style>,
+) {
+    let Ok(ColorField(color)) = fields.get(changed.target()) else {
+        return;
+    };
+
+    if let Ok(value) = Srgba::hex(&changed.0) {
+        let value = value.into();
+        if style.color(*color) != value {
+            *style.color_mut(*color) = value;
+        }
+    }
+}
+
+/// Goes back to the current color if what was typed isn't valid.
+fn color_field_submitted(
+    submitted: Trigger<TextSubmitted>,
+    style: Res<Style>,
+    mut fields: Query<(&ColorField, &mut TextField)>,
+) {
+    let Ok((ColorField(color), mut field)) = fields.get_mut(submitted.target()) else {
+        return;
+    };
+
+    if Srgba::hex(&submitted.0).is_err() {
+        warn!("'{}' is not a hex color", submitted.0);
+        field.value = color_to_hex(style.color(*color));
+    }
+}
+
+/// Shows the current colors in the fields that aren't being typed in,
+/// such as after picking a preset or leaving a field half typed.
+fn reset_color_fields(
+    style: Res<Style>,
+    mut fields: Query<(&ColorField, &mut TextField), Without<Editing>>,
+) {
+    for (ColorField(color), mut field) in fields.iter_mut() {
+        let hex = color_to_hex(style.color(*color));
+        if Srgba::hex(&field.value).ok().map(Color::from) != Some(style.color(*color)) {
+            field.value = hex;
+        }
+    }
+}
+
+fn update_theme_preview(
+    style: Res<Style>,
+    mut swatches: Query<(&ColorSwatch, &mut BackgroundColor)>,
+) {
+    for (ColorSwatch(color), mut background) in swatches.iter_mut() {
+        background.0 = style.color(*color);
+    }
+}
+
+fn update_preset_dropdown(
+    style: Res<Style>,
+    presets: Res<ThemePresets>,
+    mut dropdowns: Query<&mut Dropdown, With<PresetDropdown>>,
+) {
+    let Dropdown { options, selected } = preset_dropdown(&presets, &style.theme(&presets));
+    for mut dropdown in dropdowns.iter_mut() {
+        if dropdown.options != options || dropdown.selected != selected {
+            dropdown.options = options.clone();
+            dropdown.selected = selected;
+        }
+    }
+}
+
+fn theme_button_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut style: ResMut<Style>,
+    target_query: Query<&ThemeButtonAction>,
+) {
+    if click.button == PointerButton::Primary {
+        match target_query.get(click.target()) {
+            Ok(ThemeButtonAction::ResetToDefault) => style.set_theme(&Theme::default()),
+            Err(_) => return,
+        }
+    }
+
+    click.propagate(false);
+}
@@ -0,0 +1,1034 @@
+//! Reusable menu widgets, styled from [`Style`].
+//!
+//! Each widget is spawned by a function that returns its [`EntityCommands`],
+//! and triggers an event on its entity when the player changes it, e.g.
+//! ```ignore
+//! slider(builder, &style, Slider::new(settings.music, 0.0, 1.0, 0.05))
+//!     .observe(|changed: Trigger<SliderChanged>, mut settings: ResMut<SoundSettings>| {
+//!         settings.music = changed.0;
+//!     });
+//! ```
+//! The widget components can also be changed directly, such as when the
+//! setting is changed from elsewhere, which updates how they look without
+//! triggering the event.
+use super::*;
+use crate::controls::ControlsSystem;
+use crate::locale::localize_texts;
+use crate::prelude::*;
+use crate::settings::{ReflectSettings, SettingKind, SettingValue};
+use crate::style::restyle;
+
+use bevy::{
+    ecs::{hierarchy::ChildSpawnerCommands, system::EntityCommands},
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+    ui::RelativeCursorPosition,
+};
+
+pub const WIDGET_HEIGHT: f32 = 65.0;
+/// The width of the labels in a [`setting_row`].
+const SETTING_LABEL_WIDTH: f32 = 300.0;
+const SETTING_WIDGET_WIDTH: f32 = 300.0;
+const SLIDER_HEIGHT: f32 = 40.0;
+const CHECKBOX_SIZE: f32 = 32.0;
+const CHECKBOX_BORDER: f32 = 4.0;
+const TEXT_FIELD_CURSOR: &str = "_";
+
+pub struct MenuWidgetsPlugin;
+
+impl Plugin for MenuWidgetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            text_field_input
+                .after(ControlsSystem::UpdateControls)
+                .run_if(not(in_state(MenuState::Disabled))),
+        )
+        .add_systems(
+            Update,
+            (
+                (slider_drag, horizontal_widget_input).run_if(not(in_state(MenuState::Disabled))),
+                text_field_stop_on_click,
+                (
+                    update_sliders,
+                    update_checkboxes,
+                    update_cycle_selects,
+                    update_dropdowns,
+                    // Placeholders are faded after the text is restyled.
+                    update_text_fields.after(restyle),
+                )
+                    .before(localize_texts),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// The text style used by all of the widgets.
+pub fn text_style(style: &Style) -> (TextFont, TextColor, StyleRole, TextSize) {
+    style.text(StyleRole::Text, TextSize::Body)
+}
+
+/// The node of a menu button with the given width.
+pub fn button_node(width: f32) -> Node {
+    Node {
+        width: Val::Px(width),
+        height: Val::Px(WIDGET_HEIGHT),
+        margin: UiRect::all(Val::Px(20.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+/// The root of a menu screen, centering its content.
+pub fn menu_root(marker: impl Component) -> impl Bundle {
+    (
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        marker,
+    )
+}
+
+/// A centered column for the content of a menu screen.
+pub fn menu_column() -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+/// Spawns a button with centered text.
+pub fn button<'a>(
+    builder: &'a mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    text: impl Into<Localized>,
+    width: f32,
+) -> EntityCommands<'a> {
+    builder.spawn((
+        Button,
+        button_node(width),
+        style.background(StyleRole::Button),
+        children![(localized(text), text_style(style), Pickable::IGNORE)],
+    ))
+}
+
+/// A row spawned by [`setting_row`], whose label names the widgets in it.
+#[derive(Component, Clone, Debug)]
+pub struct SettingRow(pub Localized);
+
+/// Spawns a row with a label on the left and the widget(s) on the right.
+pub fn setting_row(
+    builder: &mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    label: impl Into<Localized>,
+    widget: impl FnOnce(&mut ChildSpawnerCommands),
+) {
+    let label = label.into();
+    builder
+        .spawn((
+            SettingRow(label.clone()),
+            Node {
+                align_items: AlignItems::Center,
+                margin: UiRect::vertical(Val::Px(10.0)),
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                localized(label),
+                text_style(style),
+                Node {
+                    width: Val::Px(SETTING_LABEL_WIDTH),
+                    ..default()
+                },
+            ));
+            builder
+                .spawn(Node {
+                    width: Val::Px(SETTING_WIDGET_WIDTH),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                })
+                .with_children(widget);
+        });
+}
+
+/// Spawns a column that scrolls when its children don't fit in `height`.
+pub fn scroll_list<'a>(
+    builder: &'a mut ChildSpawnerCommands<'_>,
+    height: Val,
+) -> EntityCommands<'a> {
+    let mut list = builder.spawn(Node {
+        height,
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        overflow: Overflow::scroll_y(),
+        ..default()
+    });
+    list.observe(update_scroll_position_event);
+    list
+}
+
+/// Finds the descendant of a widget with the component `T`.
+fn find_part<T: Component>(
+    entity: Entity,
+    children: &Query<&Children>,
+    parts: &Query<Entity, With<T>>,
+) -> Option<Entity> {
+    children
+        .iter_descendants(entity)
+        .find(|descendant| parts.contains(*descendant))
+}
+
+/// A slider picking a value in a range.
+#[derive(Component, Clone, Debug)]
+#[require(Button, RelativeCursorPosition, HorizontalInput)]
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// The value is rounded to multiples of this, and changed by this with the keyboard.
+    pub step: f32,
+    /// Formats the value to show on the slider.
+    pub format: fn(f32) -> String,
+}
+
+impl Slider {
+    pub fn new(value: f32, min: f32, max: f32, step: f32) -> Self {
+        Self {
+            value,
+            min,
+            max,
+            step,
+            format: |value| format!("{:.0}%", value * 100.0),
+        }
+    }
+
+    pub fn with_format(mut self, format: fn(f32) -> String) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn fraction(&self) -> f32 {
+        ((self.value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+    }
+
+    /// Sets the value, rounded to a whole number of steps from `min`, returning whether it changed.
+    fn set(&mut self, value: f32) -> bool {
+        let steps = ((value - self.min) / self.step).round();
+        let value = (self.min + steps * self.step).clamp(self.min, self.max);
+        let changed = value != self.value;
+        self.value = value;
+        changed
+    }
+}
+
+/// Triggered on a [`Slider`] when the player changes it.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SliderChanged(pub f32);
+
+#[derive(Component)]
+struct SliderFill;
+
+#[derive(Component)]
+struct SliderText;
+
+pub fn slider<'a>(
+    builder: &'a mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    slider: Slider,
+) -> EntityCommands<'a> {
+    let fraction = slider.fraction();
+    let text = (slider.format)(slider.value);
+
+    builder.spawn((
+        slider,
+        Node {
+            width: Val::Px(SETTING_WIDGET_WIDTH),
+            height: Val::Px(SLIDER_HEIGHT),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        style.background(StyleRole::Button),
+        children![
+            (
+                SliderFill,
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    width: Val::Percent(fraction * 100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                style.background(StyleRole::Fill),
+                Pickable::IGNORE,
+            ),
+            (
+                SliderText,
+                Text::new(text),
+                style.text(StyleRole::Text, TextSize::Compact),
+                Pickable::IGNORE,
+            )
+        ],
+    ))
+}
+
+/// Spawns a [`setting_row`] for each of the fields of `settings`, with the
+/// widget for its [`SettingKind`], which changes the field in the resource.
+pub fn settings_rows<T: ReflectSettings>(
+    builder: &mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    settings: &T,
+) {
+    for field in T::FIELDS {
+        let Some(value) = settings.setting(field) else {
+            continue;
+        };
+
+        setting_row(builder, style, field.label, |builder| {
+            match (field.kind, value) {
+                (
+                    SettingKind::Number {
+                        min,
+                        max,
+                        step,
+                        format,
+                    },
+                    SettingValue::Number(value),
+                ) => {
+                    slider(
+                        builder,
+                        style,
+                        Slider::new(value, min, max, step).with_format(format),
+                    )
+                    .observe(
+                        move |changed: Trigger<SliderChanged>, mut settings: ResMut<T>| {
+                            settings.set_setting(field, SettingValue::Number(changed.0));
+                        },
+                    );
+                }
+                (SettingKind::Toggle, SettingValue::Toggle(checked)) => {
+                    checkbox(builder, style, checked).observe(
+                        move |changed: Trigger<CheckboxChanged>, mut settings: ResMut<T>| {
+                            settings.set_setting(field, SettingValue::Toggle(changed.0));
+                        },
+                    );
+                }
+                (SettingKind::Choice(options), SettingValue::Choice(variant)) => {
+                    cycle_select(
+                        builder,
+                        style,
+                        CycleSelect::new(
+                            options.iter().map(|(_, label)| *label),
+                            options
+                                .iter()
+                                .position(|(option, _)| *option == variant)
+                                .unwrap_or_default(),
+                        ),
+                    )
+                    .observe(
+                        move |changed: Trigger<SelectChanged>, mut settings: ResMut<T>| {
+                            settings.set_setting(field, SettingValue::Choice(options[changed.0].0));
+                        },
+                    );
+                }
+                _ => {}
+            }
+        });
+    }
+}
+
+/// Sets the value of a slider to where it is being pressed.
+fn slider_drag(
+    mut commands: Commands,
+    mut sliders: Query<(Entity, &Interaction, &RelativeCursorPosition, &mut Slider)>,
+) {
+    for (entity, interaction, cursor, mut slider) in sliders.iter_mut() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+
+        let value = slider.min + position.x.clamp(0.0, 1.0) * (slider.max - slider.min);
+        set_slider(&mut commands, entity, &mut slider, value);
+    }
+}
+
+fn update_sliders(
+    sliders: Query<(Entity, &Slider), Changed<Slider>>,
+    children: Query<&Children>,
+    fills: Query<Entity, With<SliderFill>>,
+    texts: Query<Entity, With<SliderText>>,
+    mut nodes: Query<&mut Node>,
+    mut text: Query<&mut Text>,
+) {
+    for (entity, slider) in sliders.iter() {
+        if let Some(mut node) =
+            find_part(entity, &children, &fills).and_then(|fill| nodes.get_mut(fill).ok())
+        {
+            node.width = Val::Percent(slider.fraction() * 100.0);
+        }
+
+        if let Some(mut text) =
+            find_part(entity, &children, &texts).and_then(|part| text.get_mut(part).ok())
+        {
+            text.0 = (slider.format)(slider.value);
+        }
+    }
+}
+
+/// A box that can be checked on and off.
+#[derive(Component, Clone, Debug)]
+#[require(Button)]
+pub struct Checkbox {
+    pub checked: bool,
+}
+
+/// Triggered on a [`Checkbox`] when the player changes it.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CheckboxChanged(pub bool);
+
+#[derive(Component)]
+struct CheckboxMark;
+
+pub fn checkbox<'a>(
+    builder: &'a mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    checked: bool,
+) -> EntityCommands<'a> {
+    let mut checkbox = builder.spawn((
+        Checkbox { checked },
+        Node {
+            width: Val::Px(CHECKBOX_SIZE + CHECKBOX_BORDER * 4.0),
+            height: Val::Px(CHECKBOX_SIZE + CHECKBOX_BORDER * 4.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        style.background(StyleRole::Button),
+        children![(
+            Node {
+                width: Val::Px(CHECKBOX_SIZE),
+                height: Val::Px(CHECKBOX_SIZE),
+                border: UiRect::all(Val::Px(CHECKBOX_BORDER)),
+                padding: UiRect::all(Val::Px(CHECKBOX_BORDER)),
+                ..default()
+            },
+            BorderColor(style.text_color),
+            StyleRole::Text,
+            Pickable::IGNORE,
+            children![(
+                CheckboxMark,
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                style.background(StyleRole::Fill),
+                if checked {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                },
+                Pickable::IGNORE,
+            )],
+        )],
+    ));
+    checkbox.observe(checkbox_click);
+    checkbox
+}
+
+fn checkbox_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut checkboxes: Query<&mut Checkbox>,
+) {
+    if click.button == PointerButton::Primary {
+        if let Ok(mut checkbox) = checkboxes.get_mut(click.target()) {
+            checkbox.checked = !checkbox.checked;
+            commands.trigger_targets(CheckboxChanged(checkbox.checked), click.target());
+        }
+    }
+
+    click.propagate(false);
+}
+
+fn update_checkboxes(
+    checkboxes: Query<(Entity, &Checkbox), Changed<Checkbox>>,
+    children: Query<&Children>,
+    marks: Query<Entity, With<CheckboxMark>>,
+    mut visibility: Query<&mut Visibility>,
+) {
+    for (entity, checkbox) in checkboxes.iter() {
+        if let Some(mut visibility) =
+            find_part(entity, &children, &marks).and_then(|mark| visibility.get_mut(mark).ok())
+        {
+            *visibility = if checkbox.checked {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
+/// Picks between options by cycling through them.
+///
+/// Clicking moves to the next option, and right clicking to the previous.
+#[derive(Component, Clone, Debug)]
+#[require(Button, HorizontalInput)]
+pub struct CycleSelect {
+    pub options: Vec<Localized>,
+    pub selected: usize,
+}
+
+impl CycleSelect {
+    pub fn new(options: impl IntoIterator<Item = impl Into<Localized>>, selected: usize) -> Self {
+        Self {
+            options: options.into_iter().map(Into::into).collect(),
+            selected,
+        }
+    }
+
+    fn text(&self) -> Localized {
+        Localized::new("widget.cycle_select").with(
+            "option",
+            self.options.get(self.selected).cloned().unwrap_or_default(),
+        )
+    }
+
+    fn step(&mut self, forward: bool) {
+        let len = self.options.len().max(1);
+        self.selected = if forward {
+            (self.selected + 1) % len
+        } else {
+            (self.selected + len - 1) % len
+        };
+    }
+}
+
+/// Triggered on a [`CycleSelect`] or [`Dropdown`] when the player picks an option.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SelectChanged(pub usize);
+
+#[derive(Component)]
+struct SelectText;
+
+pub fn cycle_select<'a>(
+    builder: &'a mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    select: CycleSelect,
+) -> EntityCommands<'a> {
+    let text = select.text();
+    let mut select = builder.spawn((
+        select,
+        Node {
+            margin: UiRect::all(Val::Px(0.0)),
+            ..button_node(SETTING_WIDGET_WIDTH)
+        },
+        style.background(StyleRole::Button),
+        children![(
+            SelectText,
+            localized(text),
+            text_style(style),
+            Pickable::IGNORE
+        )],
+    ));
+    select.observe(cycle_select_click);
+    select
+}
+
+fn cycle_select_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut selects: Query<&mut CycleSelect>,
+) {
+    if let Ok(mut select) = selects.get_mut(click.target()) {
+        match click.button {
+            PointerButton::Primary => select.step(true),
+            PointerButton::Secondary => select.step(false),
+            PointerButton::Middle => return,
+        }
+        commands.trigger_targets(SelectChanged(select.selected), click.target());
+    }
+
+    click.propagate(false);
+}
+
+fn update_cycle_selects(
+    selects: Query<(Entity, &CycleSelect), Changed<CycleSelect>>,
+    children: Query<&Children>,
+    texts: Query<Entity, With<SelectText>>,
+    mut text: Query<&mut Localized>,
+) {
+    for (entity, select) in selects.iter() {
+        if let Some(mut text) =
+            find_part(entity, &children, &texts).and_then(|part| text.get_mut(part).ok())
+        {
+            *text = select.text();
+        }
+    }
+}
+
+/// Changes sliders and cycle selects with left and right while they have focus.
+fn horizontal_widget_input(
+    mut commands: Commands,
+    focus: Res<MenuFocus>,
+    input: Res<ControlState>,
+    mut sliders: Query<&mut Slider>,
+    mut selects: Query<&mut CycleSelect>,
+) {
+    let Some(entity) = focus.entity.filter(|_| focus.visible) else {
+        return;
+    };
+
+    let direction = input.just_pressed(Control::MoveRight) as i8 as f32
+        - input.just_pressed(Control::MoveLeft) as i8 as f32;
+    if direction == 0.0 {
+        return;
+    }
+
+    step_widget(
+        &mut commands,
+        entity,
+        direction > 0.0,
+        &mut sliders,
+        &mut selects,
+    );
+}
+
+/// Steps a slider or cycle select forward or back, as if by the player.
+pub(super) fn step_widget(
+    commands: &mut Commands,
+    entity: Entity,
+    forward: bool,
+    sliders: &mut Query<&mut Slider>,
+    selects: &mut Query<&mut CycleSelect>,
+) {
+    if let Ok(mut slider) = sliders.get_mut(entity) {
+        let direction = if forward { 1.0 } else { -1.0 };
+        let value = slider.value + slider.step * direction;
+        set_slider(commands, entity, &mut slider, value);
+    }
+
+    if let Ok(mut select) = selects.get_mut(entity) {
+        select.step(forward);
+        commands.trigger_targets(SelectChanged(select.selected), entity);
+    }
+}
+
+/// Sets the value of a slider as if by the player, triggering [`SliderChanged`] if it changed.
+pub(super) fn set_slider(
+    commands: &mut Commands,
+    entity: Entity,
+    slider: &mut Mut<Slider>,
+    value: f32,
+) {
+    if slider.bypass_change_detection().set(value) {
+        slider.set_changed();
+        commands.trigger_targets(SliderChanged(slider.value), entity);
+    }
+}
+
+/// Picks between options from a list that opens when clicked.
+#[derive(Component, Clone, Debug)]
+#[require(Button)]
+pub struct Dropdown {
+    pub options: Vec<Localized>,
+    pub selected: usize,
+}
+
+impl Dropdown {
+    pub fn new(options: impl IntoIterator<Item = impl Into<Localized>>, selected: usize) -> Self {
+        Self {
+            options: options.into_iter().map(Into::into).collect(),
+            selected,
+        }
+    }
+
+    fn text(&self) -> Localized {
+        self.options.get(self.selected).cloned().unwrap_or_default()
+    }
+}
+
+/// The open list of a [`Dropdown`].
+#[derive(Component)]
+struct DropdownList;
+
+#[derive(Component)]
+struct DropdownOption {
+    dropdown: Entity,
+    index: usize,
+}
+
+pub fn dropdown<'a>(
+    builder: &'a mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    dropdown: Dropdown,
+) -> EntityCommands<'a> {
+    let text = dropdown.text();
+    let mut dropdown = builder.spawn((
+        dropdown,
+        Node {
+            margin: UiRect::all(Val::Px(0.0)),
+            ..button_node(SETTING_WIDGET_WIDTH)
+        },
+        style.background(StyleRole::Button),
+        children![(
+            SelectText,
+            localized(text),
+            text_style(style),
+            Pickable::IGNORE
+        )],
+    ));
+    dropdown.observe(dropdown_click);
+    dropdown
+}
+
+/// Opens or closes the list of options.
+fn dropdown_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut focus: ResMut<MenuFocus>,
+    style: Res<Style>,
+    dropdowns: Query<&Dropdown>,
+    lists: Query<(Entity, &ChildOf), With<DropdownList>>,
+) {
+    let target = click.target();
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Ok(dropdown) = dropdowns.get(target) else {
+        return;
+    };
+
+    let open = lists
+        .iter()
+        .find(|(_, child_of)| child_of.parent() == target)
+        .map(|(list, _)| list);
+
+    if let Some(list) = open {
+        commands.entity(list).despawn();
+        return;
+    }
+
+    let mut selected = None;
+    commands.entity(target).with_children(|builder| {
+        builder
+            .spawn((
+                DropdownList,
+                FocusTrap,
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(100.0),
+                    left: Val::Px(0.0),
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                GlobalZIndex(1),
+                style.background(StyleRole::Background),
+            ))
+            .with_children(|builder| {
+                for (index, option) in dropdown.options.iter().enumerate() {
+                    let id = builder
+                        .spawn((
+                            Button,
+                            Node {
+                                margin: UiRect::all(Val::Px(0.0)),
+                                ..button_node(SETTING_WIDGET_WIDTH)
+                            },
+                            style.background(StyleRole::Button),
+                            DropdownOption {
+                                dropdown: target,
+                                index,
+                            },
+                            children![(
+                                localized(option.clone()),
+                                text_style(&style),
+                                Pickable::IGNORE
+                            )],
+                        ))
+                        .observe(dropdown_option_click)
+                        .id();
+
+                    if index == dropdown.selected {
+                        selected = Some(id);
+                    }
+                }
+            });
+    });
+
+    // Keep the focus in the list while it is open.
+    if focus.visible {
+        focus.entity = selected;
+    }
+}
+
+fn dropdown_option_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut focus: ResMut<MenuFocus>,
+    mut dropdowns: Query<&mut Dropdown>,
+    options: Query<&DropdownOption>,
+    parents: Query<&ChildOf>,
+) {
+    let target = click.target();
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Ok(option) = options.get(target) else {
+        return;
+    };
+
+    if let Ok(mut dropdown) = dropdowns.get_mut(option.dropdown) {
+        dropdown.selected = option.index;
+        commands.trigger_targets(SelectChanged(option.index), option.dropdown);
+    }
+
+    if let Ok(ChildOf(list)) = parents.get(target) {
+        commands.entity(*list).despawn();
+    }
+
+    if focus.visible {
+        focus.entity = Some(option.dropdown);
+    }
+}
+
+fn update_dropdowns(
+    dropdowns: Query<(Entity, &Dropdown), Changed<Dropdown>>,
+    children: Query<&Children>,
+    texts: Query<Entity, With<SelectText>>,
+    mut text: Query<&mut Localized>,
+) {
+    for (entity, dropdown) in dropdowns.iter() {
+        if let Some(mut text) =
+            find_part(entity, &children, &texts).and_then(|part| text.get_mut(part).ok())
+        {
+            *text = dropdown.text();
+        }
+    }
+}
+
+/// A field the player can type text into.
+///
+/// Clicking it starts editing, and `Enter` or `Escape` stops.
+#[derive(Component, Clone, Debug)]
+#[require(Button)]
+pub struct TextField {
+    pub value: String,
+    /// Shown when the value is empty.
+    pub placeholder: Localized,
+    pub max_len: usize,
+}
+
+impl TextField {
+    pub fn new(value: impl Into<String>, max_len: usize) -> Self {
+        Self {
+            value: value.into(),
+            placeholder: Localized::default(),
+            max_len,
+        }
+    }
+
+    pub fn with_placeholder(mut self, placeholder: impl Into<Localized>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+}
+
+/// Marks the [`TextField`] being typed in.
+#[derive(Component)]
+pub struct Editing;
+
+/// Triggered on a [`TextField`] each time the player edits it.
+#[derive(Event, Debug, Clone)]
+pub struct TextChanged(pub String);
+
+/// Triggered on a [`TextField`] when the player presses `Enter`.
+#[derive(Event, Debug, Clone)]
+pub struct TextSubmitted(pub String);
+
+#[derive(Component)]
+struct TextFieldText;
+
+pub fn text_field<'a>(
+    builder: &'a mut ChildSpawnerCommands<'_>,
+    style: &Style,
+    field: TextField,
+) -> EntityCommands<'a> {
+    let mut field = builder.spawn((
+        field,
+        Node {
+            margin: UiRect::all(Val::Px(0.0)),
+            padding: UiRect::horizontal(Val::Px(10.0)),
+            justify_content: JustifyContent::Start,
+            overflow: Overflow::clip(),
+            ..button_node(SETTING_WIDGET_WIDTH)
+        },
+        style.background(StyleRole::Button),
+        children![(
+            TextFieldText,
+            Text::default(),
+            text_style(style),
+            Pickable::IGNORE
+        )],
+    ));
+    field.observe(text_field_click);
+    field
+}
+
+fn text_field_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    fields: Query<(), With<TextField>>,
+    editing: Query<Entity, With<Editing>>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary || !fields.contains(click.target()) {
+        return;
+    }
+
+    for entity in editing.iter() {
+        commands.entity(entity).remove::<Editing>();
+    }
+    commands.entity(click.target()).insert(Editing);
+}
+
+/// Stops editing when the player clicks somewhere else.
+fn text_field_stop_on_click(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    editing: Query<(Entity, &Interaction), With<Editing>>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    for (entity, interaction) in editing.iter() {
+        if *interaction == Interaction::None {
+            commands.entity(entity).remove::<Editing>();
+        }
+    }
+}
+
+/// Types into the field being edited.
+///
+/// This runs before the menus read the [`ControlState`], and clears it
+/// so that typing doesn't also navigate or leave the menu.
+fn text_field_input(
+    mut commands: Commands,
+    mut keys: EventReader<KeyboardInput>,
+    mut control_state: ResMut<ControlState>,
+    mut fields: Query<(Entity, &mut TextField), With<Editing>>,
+) {
+    let Ok((entity, mut field)) = fields.single_mut() else {
+        keys.clear();
+        return;
+    };
+
+    control_state.clear();
+
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &key.logical_key {
+            Key::Enter => {
+                commands.entity(entity).remove::<Editing>();
+                commands.trigger_targets(TextSubmitted(field.value.clone()), entity);
+                return;
+            }
+            Key::Escape => {
+                commands.entity(entity).remove::<Editing>();
+                return;
+            }
+            Key::Backspace => {
+                if field.value.pop().is_some() {
+                    commands.trigger_targets(TextChanged(field.value.clone()), entity);
+                }
+            }
+            Key::Space | Key::Character(_) => {
+                let text = match &key.logical_key {
+                    Key::Character(text) => text.as_str(),
+                    _ => " ",
+                };
+
+                let mut changed = false;
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    if field.value.chars().count() < field.max_len {
+                        field.value.push(c);
+                        changed = true;
+                    }
+                }
+
+                if changed {
+                    commands.trigger_targets(TextChanged(field.value.clone()), entity);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn update_text_fields(
+    style: Res<Style>,
+    messages: Res<Messages>,
+    fields: Query<(Entity, &TextField, Has<Editing>)>,
+    changed: Query<(), Or<(Changed<TextField>, Added<Editing>)>>,
+    mut stopped: RemovedComponents<Editing>,
+    children: Query<&Children>,
+    texts: Query<Entity, With<TextFieldText>>,
+    mut text: Query<(&mut Text, &mut TextColor)>,
+) {
+    let stopped: Vec<Entity> = stopped.read().collect();
+
+    for (entity, field, editing) in fields.iter() {
+        if !style.is_changed()
+            && !messages.is_changed()
+            && !changed.contains(entity)
+            && !stopped.contains(&entity)
+        {
+            continue;
+        }
+
+        let Some((mut text, mut color)) =
+            find_part(entity, &children, &texts).and_then(|part| text.get_mut(part).ok())
+        else {
+            continue;
+        };
+
+        if field.value.is_empty() && !editing {
+            text.0 = messages.format(&field.placeholder);
+            // Placeholders are drawn faded.
+            color.0 = style.text_color.with_alpha(0.5);
+        } else {
+            text.0 = field.value.clone();
+            if editing {
+                text.0.push_str(TEXT_FIELD_CURSOR);
+            }
+            color.0 = style.text_color;
+        }
+    }
+}
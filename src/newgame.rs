@@ -1,8 +1,13 @@
+use crate::camera::CameraTarget;
+use crate::database::SetKvError;
+use crate::day_night::GameClock;
 use crate::prelude::*;
+use crate::prompt::{InputPrompt, prompt_node};
 use bevy::prelude::*;
 use bevy_ecs_tilemap::helpers::hex_grid::axial::AxialPos;
 use bevy_ecs_tilemap::prelude::*;
 use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 //use crate::tiles::spawn_tile_labels;
 
 pub struct NewGamePlugin;
@@ -10,11 +15,25 @@ pub struct NewGamePlugin;
 const ROOM_SIZE: TilemapSize = TilemapSize { x: 21, y: 21 };
 const ROOM_TILE_LAYER: f32 = 0.0;
 const RADIUS: u32 = 10;
+const SAVED_GAME_DB_KEY: &str = "saved_game";
+/// Keeps the seeds of new games from matching the sky's.
+const SESSION_SEED_SALT: u64 = 0x4741_4d45_5345_4544;
 
 impl Plugin for NewGamePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(TileRand(RandomSource::from_os_rng()))
-            .add_systems(OnEnter(GameState::Game), spawn_room);
+        app.init_resource::<SessionSeeds>()
+            .add_systems(
+                OnEnter(GameState::Game),
+                (init_resource::<TileRand>, (spawn_room, spawn_hud)).chain(),
+            )
+            .add_systems(
+                OnExit(GameState::Game),
+                (
+                    despawn_all_with::<OnGame>,
+                    remove_resource::<TileRand>,
+                    remove_resource::<GameSession>,
+                ),
+            );
     }
 }
 
@@ -26,9 +45,113 @@ pub struct RoomTile;
 #[reflect(Component)]
 pub struct RoomTileMap;
 
+/// Marks everything belonging to the game being played,
+/// which is despawned when leaving the game.
+#[derive(Component, Default)]
+pub struct OnGame;
+
+/// The game being played, which only exists while in [`GameState::Game`].
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct GameSession {
+    pub seed: u64,
+    /// The time the game starts at.
+    pub clock: GameClock,
+}
+
+impl From<SavedGame> for GameSession {
+    fn from(saved: SavedGame) -> Self {
+        Self {
+            seed: saved.seed,
+            clock: saved.clock,
+        }
+    }
+}
+
+impl GameSession {
+    /// The game to save, which has got to the time of the `clock`.
+    pub fn to_saved(&self, clock: &GameClock) -> SavedGame {
+        SavedGame {
+            seed: self.seed,
+            clock: *clock,
+        }
+    }
+}
+
+/// Starts playing `session`.
+pub fn start_game(commands: &mut Commands, session: GameSession) {
+    info!("Starting game with seed {}", session.seed);
+    commands.insert_resource(session);
+    commands.set_state(GameState::Game);
+}
+
+/// Picks the seeds of new games.
+///
+/// These come from the [`WorldSeed`] so that replays start the same games.
+#[derive(Resource)]
+pub struct SessionSeeds(RandomSource);
+
+impl FromWorld for SessionSeeds {
+    fn from_world(world: &mut World) -> Self {
+        let WorldSeed(seed) = *world.resource::<WorldSeed>();
+        Self(RandomSource::seed_from_u64(seed ^ SESSION_SEED_SALT))
+    }
+}
+
+impl SessionSeeds {
+    pub fn new_session(&mut self) -> GameSession {
+        GameSession {
+            seed: self.0.random(),
+            clock: GameClock::default(),
+        }
+    }
+}
+
+/// Everything needed to continue a game.
+///
+/// The world is generated from the seed, so it is all that is needed besides the time.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct SavedGame {
+    pub seed: u64,
+    /// Missing from games saved before there was a time of day, which start in the morning.
+    #[serde(default)]
+    pub clock: GameClock,
+}
+
+impl SavedGame {
+    /// Loads the saved game, if there is one.
+    pub fn load(database: &Database) -> Option<Self> {
+        database.get_kv_or_default(SAVED_GAME_DB_KEY, None::<Self>)
+    }
+
+    pub fn save(&self, database: &Database) -> Result<(), SetKvError> {
+        database.set_kv(SAVED_GAME_DB_KEY, Some(*self))
+    }
+}
+
 #[derive(Resource)]
 struct TileRand(pub RandomSource);
 
+impl FromWorld for TileRand {
+    fn from_world(world: &mut World) -> Self {
+        let GameSession { seed, .. } = *world.resource::<GameSession>();
+        Self(RandomSource::seed_from_u64(seed))
+    }
+}
+
+fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        OnGame,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(16.0),
+            bottom: Val::Px(16.0),
+            ..prompt_node()
+        },
+        InputPrompt::new(Control::Pause),
+        Pickable::IGNORE,
+    ));
+}
+
 fn spawn_room(mut commands: Commands, asset_server: Res<AssetServer>, mut rng: ResMut<TileRand>) {
     let texture_handle: Handle<Image> = asset_server.load(TILE_ASSET_LOAD_PATH);
 
@@ -63,7 +186,10 @@ fn spawn_room(mut commands: Commands, asset_server: Res<AssetServer>, mut rng: R
     });
 
     commands.entity(tilemap_entity).insert((
+        OnGame,
         RoomTileMap,
+        // There is nobody in the room yet, so the camera follows the room itself.
+        CameraTarget,
         TilemapBundle {
             grid_size: TILE_SIZE.into(),
             map_type: TilemapType::Hexagon(HexCoordSystem::Row),
@@ -0,0 +1,133 @@
+//! Coherent noise, for shapes that are random but smooth, such as clouds.
+//!
+//! The noise only depends on its seed and where it is sampled, so the same seed gives
+//! the same shapes in any order and on any platform, unlike drawing from a
+//! [`RandomSource`](crate::prelude::RandomSource).
+use bevy::prelude::*;
+
+/// Value noise, which picks a random value for each point of a grid
+/// of whole numbers and smoothly blends between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueNoise {
+    seed: u64,
+}
+
+impl ValueNoise {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Another noise unrelated to this one, such as for each layer of something.
+    pub fn fork(self, stream: u64) -> Self {
+        Self::new(hash(self.seed, stream))
+    }
+
+    /// The random value of the grid point `cell`, from `0` to `1`.
+    fn lattice(self, cell: IVec2) -> f32 {
+        let cell = (cell.x as u32 as u64) | ((cell.y as u32 as u64) << 32);
+        // The top 24 bits, which an `f32` holds exactly.
+        (hash(self.seed, cell) >> 40) as f32 / (1u32 << 24) as f32
+    }
+
+    /// The noise at `pos`, from `0` to `1`, which changes over about one unit.
+    pub fn sample(self, pos: Vec2) -> f32 {
+        let cell = pos.floor();
+        let fraction = pos - cell;
+        // Smoothstep, so there are no creases along the grid.
+        let t = fraction * fraction * (3.0 - 2.0 * fraction);
+
+        let cell = cell.as_ivec2();
+        let bottom = self.lattice(cell).lerp(self.lattice(cell + IVec2::X), t.x);
+        let top = self
+            .lattice(cell + IVec2::Y)
+            .lerp(self.lattice(cell + IVec2::ONE), t.x);
+        bottom.lerp(top, t.y)
+    }
+
+    /// Fractal noise at `pos`, from `0` to `1`, made of `octaves` layers of noise
+    /// each with twice the detail and half the strength of the one before.
+    pub fn fractal(self, pos: Vec2, octaves: u32) -> f32 {
+        let mut total = 0.0;
+        let mut strength = 1.0;
+        let mut strengths = 0.0;
+        let mut pos = pos;
+        for octave in 0..octaves {
+            total += self.fork(octave as u64).sample(pos) * strength;
+            strengths += strength;
+            strength *= 0.5;
+            pos *= 2.0;
+        }
+        if strengths > 0.0 {
+            total / strengths
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Mixes `value` into `seed`, with the finalizer of SplitMix64.
+fn hash(seed: u64, value: u64) -> u64 {
+    let mut z = seed ^ value.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Points spread over a few cells, including their corners and edges.
+    fn points() -> impl Iterator<Item = Vec2> {
+        (-20..20).flat_map(|x| (-20..20).map(move |y| Vec2::new(x as f32, y as f32) * 0.37))
+    }
+
+    #[test]
+    fn samples_only_depend_on_the_seed_and_position() {
+        let noise = ValueNoise::new(42);
+        for pos in points() {
+            assert_eq!(noise.sample(pos), ValueNoise::new(42).sample(pos));
+            assert_eq!(
+                noise.fork(3).sample(pos),
+                ValueNoise::new(42).fork(3).sample(pos)
+            );
+            assert_eq!(noise.fractal(pos, 3), ValueNoise::new(42).fractal(pos, 3));
+        }
+
+        // The values on the grid are pinned, so the clouds of a seed don't change
+        // between versions or platforms.
+        assert_eq!(
+            noise.sample(Vec2::new(3.0, -2.0)),
+            6_032_730.0 / (1u32 << 24) as f32
+        );
+
+        let differs =
+            |a: ValueNoise, b: ValueNoise| points().any(|pos| a.sample(pos) != b.sample(pos));
+        assert!(differs(noise, ValueNoise::new(43)));
+        assert!(differs(noise, noise.fork(0)));
+        assert!(differs(noise.fork(0), noise.fork(1)));
+    }
+
+    #[test]
+    fn samples_are_between_zero_and_one() {
+        let noise = ValueNoise::new(7);
+        for pos in points() {
+            assert!((0.0..=1.0).contains(&noise.sample(pos)));
+            assert!((0.0..=1.0).contains(&noise.fractal(pos, 4)));
+        }
+    }
+
+    #[test]
+    fn neighbouring_samples_are_close() {
+        let noise = ValueNoise::new(7);
+        let step = 0.001;
+        // Crossing the grid lines too, where there would be creases.
+        for pos in points().chain([Vec2::new(1.0 - step / 2.0, 2.0 - step / 2.0)]) {
+            let value = noise.sample(pos);
+            for neighbour in [pos + Vec2::X * step, pos + Vec2::Y * step] {
+                // The slope of the noise is at most 1.5 along each axis.
+                assert!((noise.sample(neighbour) - value).abs() <= 1.5 * step + 1e-5);
+            }
+        }
+    }
+}
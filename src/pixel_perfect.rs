@@ -0,0 +1,200 @@
+//! Pixel-perfect rendering of the world.
+//!
+//! When [`DisplaySettings::pixel_perfect`] is on, the [`MainCamera`] draws the world to a
+//! canvas with a pixel for each pixel of the art, which is then drawn to the window at
+//! a whole number of physical pixels per pixel, the [`PixelZoom`]. Zooming the art by
+//! a fraction with nearest sampling gives its pixels uneven sizes, which shimmer and
+//! leave seams between the hexes as the camera moves.
+use crate::camera::MainCamera;
+use crate::display::DisplaySettings;
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+    window::PrimaryWindow,
+};
+
+/// The render layer of the canvas, which only the canvas camera draws.
+const CANVAS_LAYER: usize = 1;
+/// Drawn over the [`MainCamera`], which is order `0`.
+const CANVAS_CAMERA_ORDER: isize = 1;
+
+pub struct PixelPerfectPlugin;
+
+impl Plugin for PixelPerfectPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PixelZoom>()
+            .init_resource::<PixelZoom>()
+            .add_systems(
+                Update,
+                (
+                    apply_pixel_perfect.run_if(resource_changed::<DisplaySettings>),
+                    resize_canvas.run_if(resource_exists::<PixelCanvas>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The number of physical pixels each pixel of the art is drawn with when pixel-perfect.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default, Debug, PartialEq)]
+pub struct PixelZoom(pub u32);
+
+impl Default for PixelZoom {
+    fn default() -> Self {
+        Self(2)
+    }
+}
+
+impl PixelZoom {
+    /// The zoom closest to an orthographic `scale` of world units per logical pixel.
+    pub fn from_scale(scale: f32, scale_factor: f32) -> Self {
+        Self((scale_factor / scale).round().max(1.0) as u32)
+    }
+
+    /// The world units, which are pixels of the art, for each logical pixel of the window.
+    pub fn world_per_pixel(self, scale_factor: f32) -> f32 {
+        scale_factor / self.0 as f32
+    }
+}
+
+/// The image the [`MainCamera`] draws the world to when pixel-perfect.
+#[derive(Resource, Debug)]
+struct PixelCanvas(Handle<Image>);
+
+/// Marks the camera and sprite that draw the [`PixelCanvas`] to the window.
+#[derive(Component)]
+struct OnCanvas;
+
+#[derive(Component)]
+struct CanvasSprite;
+
+/// An image that can be rendered to, of `size` pixels.
+fn canvas_image(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
+/// The size of the canvas for a window of `physical_size` pixels, which is
+/// rounded up so that the edges of the window are always covered.
+fn canvas_size(physical_size: UVec2, zoom: PixelZoom) -> UVec2 {
+    UVec2::new(
+        physical_size.x.div_ceil(zoom.0),
+        physical_size.y.div_ceil(zoom.0),
+    )
+    .max(UVec2::ONE)
+}
+
+/// Moves the [`MainCamera`] onto the canvas when pixel-perfect is turned on, and back off of it.
+fn apply_pixel_perfect(
+    mut commands: Commands,
+    display: Res<DisplaySettings>,
+    canvas: Option<Res<PixelCanvas>>,
+    on_canvas: Query<Entity, With<OnCanvas>>,
+    camera: Single<(&mut Camera, &mut Projection), With<MainCamera>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut images: ResMut<Assets<Image>>,
+    mut zoom: ResMut<PixelZoom>,
+) {
+    // There is no window to draw the canvas to when running headless.
+    let Ok(window) = window.single() else {
+        return;
+    };
+
+    let (mut camera, mut projection) = camera.into_inner();
+    let Projection::Orthographic(ref mut projection2d) = *projection else {
+        unreachable!("Only Orthographic Projection is supported!");
+    };
+
+    match (display.pixel_perfect, canvas) {
+        (true, None) => {
+            *zoom = PixelZoom::from_scale(projection2d.scale, window.scale_factor());
+            // Each pixel of the canvas is a world unit.
+            projection2d.scale = 1.0;
+
+            let image = images.add(canvas_image(canvas_size(window.physical_size(), *zoom)));
+            camera.target = RenderTarget::Image(image.clone().into());
+
+            commands.spawn((
+                OnCanvas,
+                Camera2d,
+                Camera {
+                    order: CANVAS_CAMERA_ORDER,
+                    ..default()
+                },
+                Msaa::Off,
+                RenderLayers::layer(CANVAS_LAYER),
+            ));
+            commands.spawn((
+                OnCanvas,
+                CanvasSprite,
+                Sprite::from_image(image.clone()),
+                RenderLayers::layer(CANVAS_LAYER),
+            ));
+            commands.insert_resource(PixelCanvas(image));
+        }
+        (false, Some(canvas)) => {
+            projection2d.scale = zoom.world_per_pixel(window.scale_factor());
+            camera.target = RenderTarget::default();
+
+            images.remove(&canvas.0);
+            for entity in on_canvas.iter() {
+                commands.entity(entity).despawn();
+            }
+            commands.remove_resource::<PixelCanvas>();
+        }
+        _ => {}
+    }
+}
+
+/// Keeps the canvas covering the window as it is resized and zoomed.
+fn resize_canvas(
+    canvas: Res<PixelCanvas>,
+    zoom: Res<PixelZoom>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut images: ResMut<Assets<Image>>,
+    mut sprites: Query<&mut Sprite, With<CanvasSprite>>,
+) {
+    let Ok(window) = window.single() else {
+        return;
+    };
+
+    let size = canvas_size(window.physical_size(), *zoom);
+    if images
+        .get(&canvas.0)
+        .is_some_and(|image| image.size() != size)
+    {
+        if let Some(image) = images.get_mut(&canvas.0) {
+            image.resize(Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            });
+        }
+    }
+
+    // The canvas camera works in logical pixels.
+    let sprite_size = size.as_vec2() * zoom.world_per_pixel(window.scale_factor()).recip();
+    for mut sprite in sprites.iter_mut() {
+        if sprite.custom_size != Some(sprite_size) {
+            sprite.custom_size = Some(sprite_size);
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! Prompts telling the player which input to press for a control,
+//! such as "Press [A] to Select".
+//!
+//! Spawn an [`InputPrompt`] with a [`Node`] to show it in the UI, see [`prompt_node`],
+//! or with a [`Transform`] to show it in the world.
+//! The prompt owns its children, and rebuilds them whenever the
+//! binding or the device the player is using changes.
+use crate::controls::{ActiveInputDevice, Input};
+use crate::glyphs::GamepadGlyphs;
+use crate::locale::localize_texts;
+use crate::prelude::*;
+use bevy::ecs::hierarchy::ChildSpawnerCommands;
+use bevy::{prelude::*, sprite::Anchor};
+
+/// The space between the glyph and the text around it.
+const PROMPT_GAP: f32 = 8.0;
+
+pub struct PromptPlugin;
+
+impl Plugin for PromptPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_prompts.before(localize_texts));
+    }
+}
+
+/// Shows the input currently bound to a control, with text on either side.
+#[derive(Component, Clone, Debug)]
+pub struct InputPrompt {
+    pub control: Control,
+    /// The text before the input.
+    pub before: Localized,
+    /// The text after the input.
+    pub after: Localized,
+    pub font_size: TextSize,
+}
+
+impl InputPrompt {
+    /// A prompt reading "Press [input] to [control]".
+    pub fn new(control: Control) -> Self {
+        Self {
+            control,
+            before: Localized::new("prompt.press"),
+            after: Localized::new("prompt.to").with("control", control),
+            font_size: TextSize::Glyph,
+        }
+    }
+
+    pub fn with_text(mut self, before: impl Into<Localized>, after: impl Into<Localized>) -> Self {
+        self.before = before.into();
+        self.after = after.into();
+        self
+    }
+
+    pub fn with_font_size(mut self, font_size: TextSize) -> Self {
+        self.font_size = font_size;
+        self
+    }
+}
+
+/// A node laying out a UI prompt in a row.
+pub fn prompt_node() -> Node {
+    Node {
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(PROMPT_GAP),
+        ..default()
+    }
+}
+
+/// Rebuilds the prompts that changed, or all of them if anything they display changed.
+fn update_prompts(
+    mut commands: Commands,
+    style: Res<Style>,
+    messages: Res<Messages>,
+    glyphs: Res<GamepadGlyphs>,
+    controls: Res<Controls>,
+    device: Res<ActiveInputDevice>,
+    prompts: Query<(Entity, Ref<InputPrompt>, Has<Node>)>,
+) {
+    let refresh_all = style.is_changed()
+        || messages.is_changed()
+        || glyphs.is_changed()
+        || controls.is_changed()
+        || device.is_changed();
+
+    for (entity, prompt, is_ui) in prompts.iter() {
+        if !refresh_all && !prompt.is_changed() {
+            continue;
+        }
+
+        let input = controls.prompt_input(prompt.control, device.0);
+
+        let mut entity = commands.entity(entity);
+        entity.despawn_related::<Children>();
+
+        if is_ui {
+            entity.with_children(|builder| ui_prompt(builder, &style, &glyphs, &prompt, &input));
+        } else {
+            entity.with_children(|builder| {
+                world_prompt(builder, &style, &messages, &glyphs, &prompt, &input)
+            });
+        }
+    }
+}
+
+fn prompt_text(style: &Style, prompt: &InputPrompt, text: Localized) -> impl Bundle {
+    (
+        localized(text),
+        style.text(StyleRole::Text, prompt.font_size),
+        Label,
+        Pickable::IGNORE,
+    )
+}
+
+/// The text shown in place of a prompt when its control has no input.
+fn not_bound_text(prompt: &InputPrompt) -> Localized {
+    Localized::new("prompt.not_bound").with("control", prompt.control)
+}
+
+fn ui_prompt(
+    builder: &mut ChildSpawnerCommands,
+    style: &Style,
+    glyphs: &GamepadGlyphs,
+    prompt: &InputPrompt,
+    input: &Option<Input>,
+) {
+    let Some(input) = input else {
+        builder.spawn(prompt_text(style, prompt, not_bound_text(prompt)));
+        return;
+    };
+
+    if !prompt.before.is_empty() {
+        builder.spawn(prompt_text(style, prompt, prompt.before.clone()));
+    }
+
+    style.display_input(builder, glyphs, input);
+
+    if !prompt.after.is_empty() {
+        builder.spawn(prompt_text(style, prompt, prompt.after.clone()));
+    }
+}
+
+fn world_text(style: &Style, font_size: TextSize, text: String, anchor: Anchor) -> impl Bundle {
+    (
+        Text2d::new(text),
+        style.text(StyleRole::Text, font_size),
+        anchor,
+    )
+}
+
+fn world_label(style: &Style, label: String) -> impl Bundle {
+    (
+        Text2d::new(label),
+        style.text(StyleRole::GlyphLabel, TextSize::GlyphLabel),
+        Anchor::Center,
+    )
+}
+
+/// Spawns the prompt centered on the glyph, with the text on either side of it.
+///
+/// World text is written out here, as [`Localized`] only rewrites UI text,
+/// and the prompts are rebuilt when the language changes anyway.
+fn world_prompt(
+    builder: &mut ChildSpawnerCommands,
+    style: &Style,
+    messages: &Messages,
+    glyphs: &GamepadGlyphs,
+    prompt: &InputPrompt,
+    input: &Option<Input>,
+) {
+    let Some(input) = input else {
+        builder.spawn(world_text(
+            style,
+            prompt.font_size,
+            messages.format(&not_bound_text(prompt)),
+            Anchor::Center,
+        ));
+        return;
+    };
+
+    let before = messages.format(&prompt.before);
+    let after = messages.format(&prompt.after);
+
+    // Text can't be measured before it's laid out, so when there's
+    // no glyph the input is written out along with the rest of the text.
+    let Some((sprite, size, label)) = style.input_sprite(glyphs, input) else {
+        let text = [before, messages.format(&Localized::from(*input)), after]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        builder.spawn(world_text(style, prompt.font_size, text, Anchor::Center));
+        return;
+    };
+
+    let mut glyph = builder.spawn(sprite);
+    if let Some(label) = label {
+        glyph.with_child((
+            world_label(style, messages.format(&label)),
+            // Draw the label on top of the glyph.
+            Transform::from_xyz(0.0, 0.0, 0.1),
+        ));
+    }
+
+    let offset = size.x / 2.0 + PROMPT_GAP;
+
+    if !before.is_empty() {
+        builder.spawn((
+            world_text(style, prompt.font_size, before, Anchor::CenterRight),
+            Transform::from_xyz(-offset, 0.0, 0.0),
+        ));
+    }
+
+    if !after.is_empty() {
+        builder.spawn((
+            world_text(style, prompt.font_size, after, Anchor::CenterLeft),
+            Transform::from_xyz(offset, 0.0, 0.0),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controls::InputDevice;
+    use crate::headless_app;
+
+    /// The children of `prompt`, in order.
+    fn children(app: &App, prompt: Entity) -> Vec<Entity> {
+        app.world()
+            .get::<Children>(prompt)
+            .map(|children| children.to_vec())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn world_prompts_are_rebuilt_when_the_binding_or_device_changes() {
+        let mut app = headless_app();
+        app.update();
+        let prompt = app
+            .world_mut()
+            .spawn((
+                InputPrompt::new(Control::Pause),
+                Transform::default(),
+                Visibility::default(),
+            ))
+            .id();
+        app.update();
+        let bound = children(&app, prompt);
+        assert!(!bound.is_empty());
+
+        *app.world_mut()
+            .resource_mut::<Controls>()
+            .get_control_mut(Control::Pause) = default();
+        app.update();
+        let unbound = children(&app, prompt);
+        assert_eq!(unbound.len(), 1);
+        assert!(app.world().get::<Text2d>(unbound[0]).is_some());
+        assert!(!bound.contains(&unbound[0]));
+
+        app.world_mut()
+            .resource_mut::<Controls>()
+            .reset_control(Control::Pause);
+        app.world_mut().resource_mut::<ActiveInputDevice>().0 = InputDevice::Gamepad;
+        app.update();
+        let rebuilt = children(&app, prompt);
+        assert!(!rebuilt.is_empty());
+        assert!(rebuilt.iter().all(|child| !unbound.contains(child)));
+        assert!(app.world().get_entity(unbound[0]).is_err());
+    }
+}
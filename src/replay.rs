@@ -0,0 +1,498 @@
+//! Input recording and deterministic replay.
+//!
+//! Run with `--record <path>` to save the inputs of a session, and
+//! with `--replay <path>` to play them back instead of the live devices.
+//! Adding `--headless` runs without a window or a GPU, which is useful
+//! for checking a replay still behaves the same, such as in CI. Without a
+//! window there is nowhere for the mouse to point, so the recorded mouse
+//! events are only replayed with one.
+use crate::camera::MainCamera;
+use crate::controls::{ControlsSystem, Input};
+use crate::day_night::GameClock;
+use crate::prelude::*;
+use bevy::{
+    ecs::{event::EventUpdates, system::SystemParam},
+    input::{
+        ButtonState,
+        mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
+    },
+    picking::PickSet,
+    prelude::*,
+    time::TimeUpdateStrategy,
+    window::{PrimaryWindow, WindowEvent},
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// The plugin to record and replay inputs.
+///
+/// This has to be added before any plugin that uses the [`WorldSeed`].
+#[derive(Default)]
+pub struct ReplayPlugin {
+    pub mode: ReplayMode,
+    /// Whether the game should be run without a window.
+    pub headless: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum ReplayMode {
+    #[default]
+    Live,
+    Record(PathBuf),
+    Replay(PathBuf, Recording),
+}
+
+#[derive(Error, Debug)]
+pub enum ArgsError {
+    #[error("`{0}` expects a file path")]
+    MissingPath(&'static str),
+    #[error("Unknown argument '{0}'")]
+    Unknown(String),
+    #[error("Failed to load the replay '{}' with: {source}", .path.display())]
+    Replay {
+        path: PathBuf,
+        source: RecordingError,
+    },
+}
+
+impl ReplayPlugin {
+    /// Reads the replay settings from the command line arguments.
+    ///
+    /// The replay is loaded here, so a replay that can't be played stops the game
+    /// from starting. This runs before the logger exists, so the caller has to
+    /// report the error later.
+    pub fn from_args() -> Result<Self, ArgsError> {
+        let mut mode = ReplayMode::Live;
+        let mut headless = false;
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--record" => {
+                    let path = args.next().ok_or(ArgsError::MissingPath("--record"))?;
+                    mode = ReplayMode::Record(path.into());
+                }
+                "--replay" => {
+                    let path: PathBuf = args
+                        .next()
+                        .ok_or(ArgsError::MissingPath("--replay"))?
+                        .into();
+                    let recording = Recording::load(&path).map_err(|source| ArgsError::Replay {
+                        path: path.clone(),
+                        source,
+                    })?;
+                    mode = ReplayMode::Replay(path, recording);
+                }
+                "--headless" => headless = true,
+                _ => return Err(ArgsError::Unknown(arg)),
+            }
+        }
+
+        Ok(Self { mode, headless })
+    }
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        match &self.mode {
+            ReplayMode::Live => {
+                app.insert_resource(WorldSeed(rand::random()));
+            }
+            ReplayMode::Record(path) => {
+                let seed = rand::random();
+                info!("Recording inputs to '{}' with seed {seed}", path.display());
+
+                app.insert_resource(WorldSeed(seed))
+                    .insert_resource(Recorder {
+                        path: path.clone(),
+                        recording: Recording {
+                            seed,
+                            controls: Vec::new(),
+                            frames: Vec::new(),
+                        },
+                    })
+                    .add_systems(PostStartup, record_controls)
+                    .add_systems(
+                        PreUpdate,
+                        record_frame.after(ControlsSystem::UpdateControls),
+                    )
+                    .add_systems(Last, (record_state, save_recording).chain());
+            }
+            ReplayMode::Replay(path, recording) => {
+                info!(
+                    "Replaying {} frames from '{}' with seed {}",
+                    recording.frames.len(),
+                    path.display(),
+                    recording.seed
+                );
+
+                // A recording is never empty once loaded.
+                let first_delta = recording
+                    .frames
+                    .first()
+                    .map(|frame| frame.delta)
+                    .unwrap_or_default();
+
+                app.insert_resource(WorldSeed(recording.seed))
+                    .insert_resource(Controls::from_keybinds(recording.controls.clone()))
+                    .insert_resource(TimeUpdateStrategy::ManualDuration(first_delta))
+                    .insert_resource(Replay {
+                        recording: recording.clone(),
+                        frame: 0,
+                        cursor: None,
+                        mismatches: 0,
+                    })
+                    .add_systems(
+                        First,
+                        replay_mouse.after(EventUpdates).before(PickSet::Input),
+                    )
+                    .add_systems(
+                        PreUpdate,
+                        replay_input_state.in_set(ControlsSystem::UpdateInput),
+                    )
+                    .add_systems(Last, check_replay_frame);
+            }
+        }
+    }
+}
+
+/// The seed all of the world generation is derived from.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct WorldSeed(pub u64);
+
+/// Everything needed to replay a session.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Recording {
+    pub seed: u64,
+    pub controls: Vec<Keybind>,
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// The inputs of a single frame.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedFrame {
+    /// The real time the frame took.
+    pub delta: core::time::Duration,
+    pub just_pressed: Vec<Input>,
+    pub just_released: Vec<Input>,
+    /// The mouse events over the primary window, in the order they happened.
+    pub mouse: Vec<MouseEvent>,
+    /// The state of the game at the end of the frame, used to check a replay hasn't diverged.
+    pub state: FrameState,
+}
+
+/// A mouse event over the primary window.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum MouseEvent {
+    CursorMoved([f32; 2]),
+    CursorLeft,
+    Button(MouseButton, ButtonState),
+    Wheel(MouseScrollUnit, [f32; 2]),
+}
+
+/// What the game looked like at the end of a frame.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct FrameState {
+    pub game_state: String,
+    pub pause_state: Option<String>,
+    /// Where the main camera is.
+    pub camera: Option<[f32; 3]>,
+    pub clock: Option<GameClock>,
+}
+
+#[derive(Error, Debug)]
+pub enum RecordingError {
+    #[error("Failed to access the recording with `{0}`")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse the recording with `{0}`")]
+    Deserialize(#[from] ron::error::SpannedError),
+    #[error("Failed to serialize the recording with `{0}`")]
+    Serialize(#[from] ron::Error),
+    #[error("The recording has no frames to replay")]
+    Empty,
+}
+
+impl Recording {
+    /// Loads a recording, which has to have at least one frame to replay.
+    pub fn load(path: &std::path::Path) -> Result<Self, RecordingError> {
+        let recording: Self = ron::from_str(&std::fs::read_to_string(path)?)?;
+        if recording.frames.is_empty() {
+            return Err(RecordingError::Empty);
+        }
+        Ok(recording)
+    }
+
+    pub fn save(&self, path: &std::path::Path) -> Result<(), RecordingError> {
+        Ok(std::fs::write(path, ron::to_string(self)?)?)
+    }
+}
+
+#[derive(Resource)]
+pub struct Recorder {
+    path: PathBuf,
+    recording: Recording,
+}
+
+#[derive(Resource)]
+pub struct Replay {
+    recording: Recording,
+    /// The next frame to play.
+    frame: usize,
+    /// Where the replayed cursor is over the window.
+    cursor: Option<Vec2>,
+    /// The number of frames which did not match the recording.
+    mismatches: usize,
+}
+
+/// The parts of the game checked against the recording.
+#[derive(SystemParam)]
+struct GameSnapshot<'w> {
+    game_state: Res<'w, State<GameState>>,
+    pause_state: Option<Res<'w, State<PauseState>>>,
+    camera: Option<Single<'w, &'static Transform, With<MainCamera>>>,
+    clock: Option<Res<'w, GameClock>>,
+}
+
+impl GameSnapshot<'_> {
+    fn state(&self) -> FrameState {
+        FrameState {
+            game_state: format!("{:?}", self.game_state.get()),
+            pause_state: self
+                .pause_state
+                .as_ref()
+                .map(|state| format!("{:?}", state.get())),
+            camera: self
+                .camera
+                .as_ref()
+                .map(|transform| transform.translation.to_array()),
+            clock: self.clock.as_deref().copied(),
+        }
+    }
+}
+
+fn record_controls(mut recorder: ResMut<Recorder>, controls: Res<Controls>) {
+    recorder.recording.controls = controls.clone().into_iter().collect();
+}
+
+fn record_frame(
+    mut recorder: ResMut<Recorder>,
+    input_state: Res<ButtonInput<Input>>,
+    mut window_events: EventReader<WindowEvent>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    time: Res<Time<Real>>,
+) {
+    let primary_window = primary_window.single().ok();
+    let mouse = window_events
+        .read()
+        .filter_map(|event| match event {
+            WindowEvent::CursorMoved(event) if Some(event.window) == primary_window => {
+                Some(MouseEvent::CursorMoved(event.position.to_array()))
+            }
+            WindowEvent::CursorLeft(event) if Some(event.window) == primary_window => {
+                Some(MouseEvent::CursorLeft)
+            }
+            WindowEvent::MouseButtonInput(event) if Some(event.window) == primary_window => {
+                Some(MouseEvent::Button(event.button, event.state))
+            }
+            WindowEvent::MouseWheel(event) if Some(event.window) == primary_window => {
+                Some(MouseEvent::Wheel(event.unit, [event.x, event.y]))
+            }
+            _ => None,
+        })
+        .collect();
+
+    recorder.recording.frames.push(RecordedFrame {
+        delta: time.delta(),
+        just_pressed: input_state.get_just_pressed().copied().collect(),
+        just_released: input_state.get_just_released().copied().collect(),
+        mouse,
+        state: FrameState::default(),
+    });
+}
+
+/// Fills in the state of the game once the frame is over.
+fn record_state(mut recorder: ResMut<Recorder>, snapshot: GameSnapshot) {
+    if let Some(frame) = recorder.recording.frames.last_mut() {
+        frame.state = snapshot.state();
+    }
+}
+
+fn save_recording(recorder: Res<Recorder>, mut exit: EventReader<AppExit>) {
+    if exit.read().next().is_none() {
+        return;
+    }
+
+    match recorder.recording.save(&recorder.path) {
+        Ok(()) => info!(
+            "Saved {} recorded frames to '{}'",
+            recorder.recording.frames.len(),
+            recorder.path.display()
+        ),
+        Err(err) => error!(
+            "Failed to save recording to '{}' with: {err}",
+            recorder.path.display()
+        ),
+    }
+}
+
+/// The mouse events the game and picking read, which are replaced while replaying.
+#[derive(SystemParam)]
+struct MouseEvents<'w> {
+    window: ResMut<'w, Events<WindowEvent>>,
+    cursor_moved: ResMut<'w, Events<CursorMoved>>,
+    buttons: ResMut<'w, Events<MouseButtonInput>>,
+    wheel: ResMut<'w, Events<MouseWheel>>,
+}
+
+impl MouseEvents<'_> {
+    /// Drops the live mouse events, keeping the other window events.
+    fn clear(&mut self) {
+        let kept: Vec<_> = self
+            .window
+            .drain()
+            .filter(|event| {
+                !matches!(
+                    event,
+                    WindowEvent::CursorMoved(_)
+                        | WindowEvent::CursorLeft(_)
+                        | WindowEvent::MouseButtonInput(_)
+                        | WindowEvent::MouseWheel(_)
+                )
+            })
+            .collect();
+        self.window.send_batch(kept);
+        self.cursor_moved.clear();
+        self.buttons.clear();
+        self.wheel.clear();
+    }
+
+    fn send_cursor_moved(&mut self, event: CursorMoved) {
+        self.window.send(WindowEvent::CursorMoved(event.clone()));
+        self.cursor_moved.send(event);
+    }
+
+    fn send_button(&mut self, event: MouseButtonInput) {
+        self.window.send(WindowEvent::MouseButtonInput(event));
+        self.buttons.send(event);
+    }
+
+    fn send_wheel(&mut self, event: MouseWheel) {
+        self.window.send(WindowEvent::MouseWheel(event));
+        self.wheel.send(event);
+    }
+}
+
+/// Sends the recorded mouse events over the primary window in place of the live ones,
+/// before picking and the input plugins read them.
+fn replay_mouse(
+    mut replay: ResMut<Replay>,
+    mut events: MouseEvents,
+    window: Option<Single<(Entity, &mut Window), With<PrimaryWindow>>>,
+) {
+    events.clear();
+
+    let Some((window_entity, mut window)) = window.map(Single::into_inner) else {
+        return;
+    };
+
+    let replay = &mut *replay;
+    let Some(frame) = replay.recording.frames.get(replay.frame) else {
+        return;
+    };
+
+    for event in frame.mouse.iter() {
+        match *event {
+            MouseEvent::CursorMoved(position) => {
+                let position = Vec2::from_array(position);
+                events.send_cursor_moved(CursorMoved {
+                    window: window_entity,
+                    position,
+                    delta: replay.cursor.map(|cursor| position - cursor),
+                });
+                replay.cursor = Some(position);
+            }
+            MouseEvent::CursorLeft => replay.cursor = None,
+            MouseEvent::Button(button, state) => events.send_button(MouseButtonInput {
+                button,
+                state,
+                window: window_entity,
+            }),
+            MouseEvent::Wheel(unit, [x, y]) => events.send_wheel(MouseWheel {
+                unit,
+                x,
+                y,
+                window: window_entity,
+            }),
+        }
+    }
+
+    if window.cursor_position() != replay.cursor {
+        window.set_cursor_position(replay.cursor);
+    }
+}
+
+/// Feeds the recorded inputs in place of the live devices.
+fn replay_input_state(
+    mut input_state: ResMut<ButtonInput<Input>>,
+    mut time_strategy: ResMut<TimeUpdateStrategy>,
+    replay: Res<Replay>,
+) {
+    input_state.bypass_change_detection().clear();
+
+    let Some(frame) = replay.recording.frames.get(replay.frame) else {
+        return;
+    };
+
+    for pressed in frame.just_pressed.iter() {
+        input_state.press(*pressed);
+    }
+
+    for released in frame.just_released.iter() {
+        input_state.release(*released);
+    }
+
+    // The time is updated at the start of the next frame,
+    // so this has to be the duration of the next frame.
+    if let Some(next) = replay.recording.frames.get(replay.frame + 1) {
+        *time_strategy = TimeUpdateStrategy::ManualDuration(next.delta);
+    }
+}
+
+/// Compares the state of the game with the recording once the frame is over,
+/// and exits once the recording is over.
+fn check_replay_frame(
+    mut replay: ResMut<Replay>,
+    mut exit: EventWriter<AppExit>,
+    snapshot: GameSnapshot,
+) {
+    let index = replay.frame;
+    let Some(frame) = replay.recording.frames.get(index) else {
+        return;
+    };
+
+    let state = snapshot.state();
+    if state != frame.state {
+        warn!(
+            "Replay diverged on frame {index}: expected {:?} found {state:?}",
+            frame.state
+        );
+        replay.mismatches += 1;
+    }
+
+    replay.frame += 1;
+
+    if replay.frame == replay.recording.frames.len() {
+        if replay.mismatches == 0 {
+            info!("Replay finished with every frame matching.");
+            exit.write(AppExit::Success);
+        } else {
+            error!(
+                "Replay finished with {} of {} frames not matching.",
+                replay.mismatches,
+                replay.recording.frames.len()
+            );
+            exit.write(AppExit::error());
+        }
+    }
+}
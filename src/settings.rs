@@ -0,0 +1,299 @@
+//! Settings resources whose fields are stored and edited through reflection.
+//!
+//! A [`ReflectSettings`] resource lists the fields the player can change, and its
+//! [`ReflectSettingsPlugin`] loads them from the database and saves them when they change,
+//! while the menus build an editor for each of them from its [`SettingKind`].
+use crate::database::SetKvError;
+use crate::prelude::*;
+use bevy::prelude::*;
+use bevy::reflect::{DynamicEnum, DynamicVariant, ReflectRef};
+use std::marker::PhantomData;
+
+/// A field in a [`ReflectSettings`] resource that the player can change.
+#[derive(Debug, Clone, Copy)]
+pub struct SettingField {
+    /// The reflection path to the field, such as `speed.x`.
+    pub path: &'static str,
+    /// The key of the message naming the field.
+    pub label: &'static str,
+    pub kind: SettingKind,
+}
+
+/// The types of fields a [`SettingField`] can be, and how they are edited.
+#[derive(Debug, Clone, Copy)]
+pub enum SettingKind {
+    /// An `f32`, `f64`, `i32` or `u32`, edited with a slider.
+    Number {
+        min: f32,
+        max: f32,
+        /// The value is rounded to multiples of this.
+        step: f32,
+        /// Formats the value to show next to it.
+        format: fn(f32) -> String,
+    },
+    /// A `bool`, edited with a checkbox.
+    Toggle,
+    /// An enum without fields, edited by cycling through these variants,
+    /// each given as its name and the key of the message naming it.
+    Choice(&'static [(&'static str, &'static str)]),
+}
+
+/// The value of a [`SettingField`], as edited by its [`SettingKind`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SettingValue {
+    Number(f32),
+    Toggle(bool),
+    /// The name of one of the variants of the [`SettingKind::Choice`].
+    Choice(&'static str),
+}
+
+/// A settings resource whose [`SettingField`]s are user preferences.
+pub trait ReflectSettings: Resource + Reflect + Default {
+    /// Starts the database key of each field, e.g. the field `speed.x`
+    /// of the prefix `sky` is stored under `sky_speed_x`.
+    const DB_PREFIX: &'static str;
+    const FIELDS: &'static [SettingField];
+
+    /// The value of `field`, or `None` if it isn't of its [`SettingKind`].
+    fn setting(&self, field: &SettingField) -> Option<SettingValue> {
+        let value = self
+            .reflect_path(field.path)
+            .inspect_err(|err| error!("Failed to read the setting {} with: {err}", field.path))
+            .ok()?;
+
+        let setting = match field.kind {
+            SettingKind::Number { .. } => number(value).map(SettingValue::Number),
+            SettingKind::Toggle => value
+                .try_downcast_ref::<bool>()
+                .copied()
+                .map(SettingValue::Toggle),
+            SettingKind::Choice(options) => match value.reflect_ref() {
+                ReflectRef::Enum(value) => options
+                    .iter()
+                    .find(|(variant, _)| *variant == value.variant_name())
+                    .map(|(variant, _)| SettingValue::Choice(variant)),
+                _ => None,
+            },
+        };
+        if setting.is_none() {
+            error!(
+                "Failed to read the setting {} as a {:?}",
+                field.path, field.kind
+            );
+        }
+        setting
+    }
+
+    fn set_setting(&mut self, field: &SettingField, setting: SettingValue) {
+        let value = match self.reflect_path_mut(field.path) {
+            Ok(value) => value,
+            Err(err) => {
+                error!("Failed to change the setting {} with: {err}", field.path);
+                return;
+            }
+        };
+
+        let changed = match (field.kind, setting) {
+            (SettingKind::Number { .. }, SettingValue::Number(number)) => set_number(value, number),
+            (SettingKind::Toggle, SettingValue::Toggle(toggle)) => value
+                .try_downcast_mut::<bool>()
+                .map(|value| *value = toggle)
+                .is_some(),
+            (SettingKind::Choice(_), SettingValue::Choice(variant)) => value
+                .try_apply(&DynamicEnum::new(variant, DynamicVariant::Unit))
+                .is_ok(),
+            _ => false,
+        };
+        if !changed {
+            error!("Failed to change the setting {} to {setting:?}", field.path);
+        }
+    }
+}
+
+/// The number in `value`, if it is one of the types of a [`SettingKind::Number`].
+fn number(value: &dyn PartialReflect) -> Option<f32> {
+    if let Some(value) = value.try_downcast_ref::<f32>() {
+        Some(*value)
+    } else if let Some(value) = value.try_downcast_ref::<f64>() {
+        Some(*value as f32)
+    } else if let Some(value) = value.try_downcast_ref::<i32>() {
+        Some(*value as f32)
+    } else {
+        value.try_downcast_ref::<u32>().map(|value| *value as f32)
+    }
+}
+
+/// Sets the number in `value` to `number`, rounded if it holds whole numbers.
+fn set_number(value: &mut dyn PartialReflect, number: f32) -> bool {
+    if let Some(value) = value.try_downcast_mut::<f32>() {
+        *value = number;
+    } else if let Some(value) = value.try_downcast_mut::<f64>() {
+        *value = number as f64;
+    } else if let Some(value) = value.try_downcast_mut::<i32>() {
+        *value = number.round() as i32;
+    } else if let Some(value) = value.try_downcast_mut::<u32>() {
+        *value = number.round() as u32;
+    } else {
+        return false;
+    }
+    true
+}
+
+/// The database key of `field` in `T`.
+fn db_key<T: ReflectSettings>(field: &SettingField) -> String {
+    format!("{}_{}", T::DB_PREFIX, field.path.replace('.', "_"))
+}
+
+/// The value of `field` in the database, or `default` if it isn't there.
+///
+/// Numbers are kept in range, and choices are stored by the name of their variant.
+fn load_setting<T: ReflectSettings>(
+    database: &Database,
+    field: &SettingField,
+    default: SettingValue,
+) -> SettingValue {
+    let key = db_key::<T>(field);
+    match (field.kind, default) {
+        (SettingKind::Number { min, max, .. }, SettingValue::Number(default)) => {
+            let value: f32 = database.get_kv_or_default(&key, default);
+            SettingValue::Number(value.clamp(min, max))
+        }
+        (SettingKind::Toggle, SettingValue::Toggle(default)) => {
+            SettingValue::Toggle(database.get_kv_or_default(&key, default))
+        }
+        (SettingKind::Choice(options), SettingValue::Choice(default)) => {
+            let variant: String = database.get_kv_or_default(&key, default.to_owned());
+            SettingValue::Choice(
+                options
+                    .iter()
+                    .find(|(option, _)| *option == variant)
+                    .map_or(default, |(option, _)| option),
+            )
+        }
+        _ => default,
+    }
+}
+
+fn save_setting<T: ReflectSettings>(
+    database: &Database,
+    field: &SettingField,
+    setting: SettingValue,
+) -> Result<(), SetKvError> {
+    let key = db_key::<T>(field);
+    match setting {
+        SettingValue::Number(value) => database.set_kv(&key, value),
+        SettingValue::Toggle(value) => database.set_kv(&key, value),
+        SettingValue::Choice(variant) => database.set_kv(&key, variant),
+    }
+}
+
+/// Loads the fields of `T` from the database, and saves them whenever `T` changes.
+pub struct ReflectSettingsPlugin<T>(PhantomData<T>);
+
+impl<T> Default for ReflectSettingsPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ReflectSettings> Plugin for ReflectSettingsPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_settings::<T>).add_systems(
+            Update,
+            settings_sync::<T>.run_if(resource_changed::<T>.and(not(resource_added::<T>))),
+        );
+    }
+}
+
+/// The values of the fields of `T` as they are in the database, in the order of its fields.
+#[derive(Resource)]
+struct SavedSettings<T> {
+    values: Vec<Option<SettingValue>>,
+    settings: PhantomData<T>,
+}
+
+fn setup_settings<T: ReflectSettings>(mut commands: Commands, database: Res<Database>) {
+    let mut settings = T::default();
+    for field in T::FIELDS {
+        if let Some(default) = settings.setting(field) {
+            settings.set_setting(field, load_setting::<T>(&database, field, default));
+        }
+    }
+    commands.insert_resource(SavedSettings::<T> {
+        values: T::FIELDS
+            .iter()
+            .map(|field| settings.setting(field))
+            .collect(),
+        settings: PhantomData,
+    });
+    commands.insert_resource(settings);
+}
+
+/// Saves the fields of `T` that changed since they were last saved.
+fn settings_sync<T: ReflectSettings>(
+    database: Res<Database>,
+    settings: Res<T>,
+    mut saved: ResMut<SavedSettings<T>>,
+) {
+    for (field, saved) in T::FIELDS.iter().zip(saved.values.iter_mut()) {
+        let Some(value) = settings.setting(field) else {
+            continue;
+        };
+        if *saved == Some(value) {
+            continue;
+        }
+
+        match save_setting::<T>(&database, field, value) {
+            Ok(()) => *saved = Some(value),
+            Err(err) => warn!(
+                "Failed to sync the setting {} to database with: {err}",
+                field.path
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::CameraMouseSettings;
+    use crate::sky::{Forecast, SkySettings};
+
+    #[test]
+    fn fields_are_read_and_changed_through_reflection() {
+        let mut sky = SkySettings::default();
+        for (field, value) in SkySettings::FIELDS.iter().zip([
+            SettingValue::Number(1.5),
+            SettingValue::Number(-3.0),
+            SettingValue::Choice("Storm"),
+        ]) {
+            sky.set_setting(field, value);
+            assert_eq!(sky.setting(field), Some(value));
+        }
+        assert_eq!(sky.speed, Vec2::new(1.5, -3.0));
+        assert_eq!(sky.forecast, Forecast::Storm);
+
+        let mut mouse = CameraMouseSettings::default();
+        let edge_scroll = &CameraMouseSettings::FIELDS[1];
+        mouse.set_setting(edge_scroll, SettingValue::Toggle(true));
+        assert!(mouse.edge_scroll);
+    }
+
+    #[test]
+    fn settings_are_loaded_from_the_database() {
+        let database = Database::open_in_memory().unwrap();
+        let forecast = &SkySettings::FIELDS[2];
+        save_setting::<SkySettings>(&database, forecast, SettingValue::Choice("Fog")).unwrap();
+        assert_eq!(
+            load_setting::<SkySettings>(&database, forecast, SettingValue::Choice("Changing")),
+            SettingValue::Choice("Fog")
+        );
+
+        let drift_x = &SkySettings::FIELDS[0];
+        save_setting::<SkySettings>(&database, drift_x, SettingValue::Number(100.0)).unwrap();
+        assert_eq!(
+            load_setting::<SkySettings>(&database, drift_x, SettingValue::Number(0.0)),
+            SettingValue::Number(20.0)
+        );
+    }
+}
@@ -1,18 +1,84 @@
 //! The infinite sky implementation
+//!
+//! The sky is drawn in [`SkyLayer`]s behind the rooms, each following the camera by a
+//! different amount for parallax, with the clouds drifting across them. The [`SkyWeather`]
+//! changes over time, changing which clouds drift in, how fast, and the color of the sky.
+//!
+//! Each layer is a ring of small Sky TileMaps, the chunks, covering the camera's view.
+//! As the layer scrolls, the chunks that fall behind the view are moved ahead of it
+//! and filled with new tiles, so only the tiles coming into view are changed.
+//!
+//! The tiles are picked from [`ValueNoise`] sampled where they are in their layer, so the
+//! clouds form shapes that keep their look as they scroll, and the same [`WorldSeed`]
+//! always draws the same sky.
+//!
+//! The [`Daylight`] tints the sky, and picks darker clouds at night and warmer ones at dawn and dusk.
+use crate::camera::{CameraSystems, MainCamera};
+use crate::day_night::{DayPhase, Daylight};
+use crate::display::DisplaySettings;
+use crate::noise::ValueNoise;
 use crate::prelude::*;
+use crate::settings::{ReflectSettings, ReflectSettingsPlugin, SettingField, SettingKind};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::window::WindowResized;
 use bevy_ecs_tilemap::prelude::*;
 use rand::{Rng, SeedableRng};
+use std::ops::Range;
 //use crate::tiles::spawn_tile_labels;
 
-const SKY_MAP_SIZE: TilemapSize = TilemapSize { x: 100, y: 100 };
-const SKY_TILE_SIZE_LOOP_THRESHOLD: Vec2 = Vec2 {
-    x: TILE_SIZE.x,
-    y: TILE_SIZE.y * 1.5,
+/// The tiles across and up each chunk of a [`SkyLayer`], in axial coordinates.
+const SKY_CHUNK_SIZE: u32 = 8;
+const SKY_CHUNK_MAP_SIZE: TilemapSize = TilemapSize {
+    x: SKY_CHUNK_SIZE,
+    y: SKY_CHUNK_SIZE,
 };
+/// The height of a row of hexes, which overlap by a quarter.
+const SKY_ROW_HEIGHT: f32 = TILE_SIZE.y * 0.75;
+/// About how wide the clouds are, in world units.
+const SKY_CLOUD_SIZE: f32 = TILE_SIZE.x * 6.0;
+/// The layers of detail in the clouds.
+const SKY_NOISE_OCTAVES: u32 = 3;
+/// How much the noise is stretched out from the middle, as fractal
+/// noise is rarely near `0` or `1` and would leave the sky flat.
+const SKY_NOISE_CONTRAST: f32 = 2.5;
+/// The layer of the nearest [`SkyLayer`], with the further ones below it.
 const SKY_TILE_LAYER: f32 = -1.;
+/// The distance between each [`SkyLayer`].
+const SKY_LAYER_SPACING: f32 = 0.1;
 const AXIAL_TRANSLATION_MATRIX: Mat2 =
     Mat2::from_cols_array(&[SQRT_3_2, 1.0 / 3.0, 0.0, 2.0 / 3.0]);
+const SKY_VARIANT_COUNT: usize = (SKY_TILE_VARIENTS.end - SKY_TILE_VARIENTS.start) as usize;
+
+/// The layers of the sky, from the furthest to the nearest.
+const SKY_LAYERS: [SkyLayer; 3] = [
+    SkyLayer {
+        parallax: 0.9,
+        drift: 0.5,
+        brightness: 0.7,
+        solid: true,
+    },
+    SkyLayer {
+        parallax: 0.75,
+        drift: 0.8,
+        brightness: 0.85,
+        solid: false,
+    },
+    SkyLayer {
+        parallax: 0.6,
+        drift: 1.0,
+        brightness: 1.0,
+        solid: false,
+    },
+];
+
+/// How many seconds the weather lasts before changing.
+const WEATHER_DURATION: Range<f32> = 60.0..180.0;
+/// How many seconds the weather takes to change.
+const WEATHER_TRANSITION: f32 = 10.0;
+/// How many steps the clouds grow or clear in as the weather changes,
+/// each of which refills the whole sky.
+const SKY_COVER_STEPS: f32 = 20.0;
 
 /// The plugin to
 pub struct SkyPlugin;
@@ -21,10 +87,24 @@ impl Plugin for SkyPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<SkyTile>()
             .register_type::<SkyTileMap>()
+            .register_type::<SkyLayer>()
             .register_type::<SkySettings>()
-            .insert_resource(SkyRand(RandomSource::from_os_rng()))
+            .register_type::<SkyWeather>()
+            .init_resource::<SkyRand>()
+            .init_resource::<SkyWeather>()
+            .add_plugins(ReflectSettingsPlugin::<SkySettings>::default())
             .add_systems(Startup, spawn_sky)
-            .add_systems(Update, sky_movement);
+            .add_systems(Update, (update_weather, (tint_sky, refill_sky)).chain())
+            .add_systems(
+                PostUpdate,
+                (
+                    resize_sky.run_if(on_event::<WindowResized>.or(camera_view_changed)),
+                    sky_movement,
+                )
+                    .chain()
+                    .after(CameraSystems)
+                    .before(TransformSystem::TransformPropagate),
+            );
     }
 }
 
@@ -38,34 +118,423 @@ pub struct SkyTile;
 #[reflect(Component)]
 pub struct SkyTileMap;
 
+/// A layer of the sky drawn at its own depth behind the rooms, the parent of its chunks.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Debug)]
+pub struct SkyLayer {
+    /// How much of the camera's movement the layer follows, where `1` stays
+    /// still on the screen as if infinitely far away.
+    pub parallax: f32,
+    /// How fast the clouds drift, as a fraction of [`SkySettings::speed`].
+    pub drift: f32,
+    /// How much the layer is darkened, so further layers fade into the distance.
+    pub brightness: f32,
+    /// Whether every tile is drawn, rather than only the clouds covering the sky.
+    pub solid: bool,
+}
+
+/// The noise the tiles of a [`SkyLayer`] are picked from.
+#[derive(Component, Debug)]
+struct SkyNoise(ValueNoise);
+
+impl SkyNoise {
+    /// The noise of the [`SkyLayer`] at `index` in [`SKY_LAYERS`].
+    fn new(seed: WorldSeed, index: usize) -> Self {
+        Self(ValueNoise::new(seed.0 ^ SKY_SEED_SALT).fork(index as u64))
+    }
+}
+
+/// How far the clouds of a [`SkyLayer`] have drifted, in world units.
+#[derive(Component, Default, Debug)]
+struct SkyScroll(Vec2);
+
+/// The chunks of a [`SkyLayer`], as a ring buffer.
+///
+/// The chunk at the axial chunk coordinate `coord` is always at the slot
+/// `coord.rem_euclid(size)`, so as the layer scrolls by a chunk, only the chunks
+/// on the edge it scrolls away from are moved over to the other edge.
+#[derive(Component, Default, Debug)]
+struct SkyRing {
+    /// The chunks across and up the ring.
+    size: IVec2,
+    /// The chunk TileMaps, row by row.
+    chunks: Vec<Entity>,
+}
+
+/// A Sky TileMap in a [`SkyRing`].
+#[derive(Component, Debug)]
+struct SkyChunk {
+    /// The axial coordinate of the chunk, in chunks, from the origin of its layer.
+    coord: IVec2,
+}
+
+/// The tint last given to the tiles of a [`SkyLayer`].
+#[derive(Component, Debug, PartialEq)]
+struct SkyTint(Color);
+
+/// What the tiles of a [`SkyLayer`] were last picked for, so they can be
+/// picked again when the sky changes rather than only as they scroll into view.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+struct SkyFill {
+    weather: Weather,
+    phase: DayPhase,
+    /// The cloud cover, in [`SKY_COVER_STEPS`].
+    cover: u32,
+}
+
+/// The random numbers the weather changes by.
 #[derive(Resource)]
 struct SkyRand(pub RandomSource);
 
+/// Mixed into the [`WorldSeed`] so the sky doesn't mirror the rooms.
+const SKY_SEED_SALT: u64 = 0x5350_4143_4520_534b;
+
+impl FromWorld for SkyRand {
+    fn from_world(world: &mut World) -> Self {
+        let WorldSeed(seed) = *world.resource::<WorldSeed>();
+        Self(RandomSource::seed_from_u64(seed ^ SKY_SEED_SALT))
+    }
+}
+
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
 pub struct SkySettings {
     /// The speed of movement in tiles per second, in axial coordinates.
     pub speed: Vec2,
+    pub forecast: Forecast,
+}
+
+impl Default for SkySettings {
+    fn default() -> Self {
+        Self {
+            speed: Vec2::new(-5.0, -2.0),
+            forecast: Forecast::Changing,
+        }
+    }
+}
+
+impl ReflectSettings for SkySettings {
+    const DB_PREFIX: &'static str = "sky";
+    const FIELDS: &'static [SettingField] = &[
+        SettingField {
+            path: "speed.x",
+            label: "sky.drift_x",
+            kind: SettingKind::Number {
+                min: -20.0,
+                max: 20.0,
+                step: 0.5,
+                format: |value| format!("{value:.1}"),
+            },
+        },
+        SettingField {
+            path: "speed.y",
+            label: "sky.drift_y",
+            kind: SettingKind::Number {
+                min: -20.0,
+                max: 20.0,
+                step: 0.5,
+                format: |value| format!("{value:.1}"),
+            },
+        },
+        SettingField {
+            path: "forecast",
+            label: "sky.forecast",
+            kind: SettingKind::Choice(&[
+                ("Changing", "forecast.changing"),
+                ("Clear", "weather.clear"),
+                ("Storm", "weather.storm"),
+                ("Fog", "weather.fog"),
+            ]),
+        },
+    ];
+}
+
+/// The weather the player wants the sky to have.
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Forecast {
+    /// The weather changes every so often.
+    #[default]
+    Changing,
+    Clear,
+    Storm,
+    Fog,
+}
+
+impl Forecast {
+    /// The weather the sky always has, if it doesn't change.
+    fn weather(self) -> Option<Weather> {
+        match self {
+            Forecast::Changing => None,
+            Forecast::Clear => Some(Weather::Clear),
+            Forecast::Storm => Some(Weather::Storm),
+            Forecast::Fog => Some(Weather::Fog),
+        }
+    }
+}
+
+/// The kinds of weather the sky can have.
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Storm,
+    Fog,
+}
+
+impl Weather {
+    const ALL: [Weather; 3] = [Weather::Clear, Weather::Storm, Weather::Fog];
+
+    /// How likely each of the [`SKY_TILE_VARIENTS`] is to drift in, where
+    /// the lighter clouds come first and the darker ones last.
+    fn variant_weights(self) -> [f32; SKY_VARIANT_COUNT] {
+        match self {
+            Weather::Clear => [4.0, 4.0, 3.0, 2.0, 1.0, 1.0, 0.5, 0.5],
+            Weather::Storm => [0.5, 0.5, 1.0, 1.0, 2.0, 3.0, 4.0, 4.0],
+            Weather::Fog => [3.0, 3.0, 3.0, 3.0, 1.0, 1.0, 1.0, 1.0],
+        }
+    }
+
+    /// The fraction of the sky covered by the clouds of the layers that aren't solid.
+    fn cloud_cover(self) -> f32 {
+        match self {
+            Weather::Clear => 0.15,
+            Weather::Storm => 0.7,
+            Weather::Fog => 0.9,
+        }
+    }
+
+    /// How fast the clouds drift, as a multiple of [`SkySettings::speed`].
+    fn drift(self) -> f32 {
+        match self {
+            Weather::Clear => 1.0,
+            Weather::Storm => 3.0,
+            Weather::Fog => 0.3,
+        }
+    }
+
+    fn tint(self) -> Color {
+        match self {
+            Weather::Clear => Color::WHITE,
+            Weather::Storm => Color::srgb(0.45, 0.5, 0.6),
+            Weather::Fog => Color::srgb(0.85, 0.85, 0.9),
+        }
+    }
+
+    /// Picks one of the [`SKY_TILE_VARIENTS`] for a `density` from `0` to `1`, where each
+    /// variant covers as much of the range as its [`Self::variant_weights`] in the `phase`.
+    fn variant(self, phase: DayPhase, density: f32) -> u32 {
+        let mut weights = self.variant_weights();
+        for (weight, scale) in weights.iter_mut().zip(phase_variant_weights(phase)) {
+            *weight *= scale;
+        }
+        let mut roll = density * weights.iter().sum::<f32>();
+        for (index, weight) in weights.into_iter().enumerate() {
+            if roll < weight {
+                return SKY_TILE_VARIENTS.start + index as u32;
+            }
+            roll -= weight;
+        }
+        SKY_TILE_VARIENTS.end - 1
+    }
+}
+
+/// How the [`Weather::variant_weights`] are scaled in each [`DayPhase`], towards
+/// the warmer middle variants at dawn and dusk and the darker ones at night.
+fn phase_variant_weights(phase: DayPhase) -> [f32; SKY_VARIANT_COUNT] {
+    match phase {
+        DayPhase::Day => [1.0; SKY_VARIANT_COUNT],
+        DayPhase::Dawn | DayPhase::Dusk => [0.5, 0.5, 1.0, 2.0, 2.0, 1.0, 0.5, 0.5],
+        DayPhase::Night => [0.25, 0.25, 0.5, 0.5, 1.0, 1.0, 2.0, 2.0],
+    }
+}
+
+/// The weather of the sky, which changes every so often.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource, Debug)]
+pub struct SkyWeather {
+    current: Weather,
+    /// The weather being changed from.
+    previous: Weather,
+    /// How far the weather has changed from the previous, from `0` to `1`.
+    transition: f32,
+    /// The seconds until the weather changes again.
+    remaining: f32,
+}
+
+impl Default for SkyWeather {
+    fn default() -> Self {
+        Self {
+            current: Weather::Clear,
+            previous: Weather::Clear,
+            transition: 1.0,
+            remaining: WEATHER_DURATION.start,
+        }
+    }
+}
+
+impl SkyWeather {
+    /// Starts changing the weather to `weather`.
+    pub fn set(&mut self, weather: Weather) {
+        if weather != self.current {
+            self.previous = self.current;
+            self.current = weather;
+            self.transition = 0.0;
+        }
+    }
+
+    /// A `value` of the weather, blended from the previous weather while it changes.
+    fn blend(&self, value: impl Fn(Weather) -> f32) -> f32 {
+        value(self.previous).lerp(value(self.current), self.transition)
+    }
+
+    fn tint(&self) -> Color {
+        self.previous
+            .tint()
+            .to_linear()
+            .mix(&self.current.tint().to_linear(), self.transition)
+            .into()
+    }
+}
+
+/// What the sky looks like, from the weather and the time of day.
+#[derive(SystemParam)]
+struct SkyConditions<'w> {
+    weather: Res<'w, SkyWeather>,
+    daylight: Res<'w, Daylight>,
+}
+
+impl SkyConditions<'_> {
+    /// The tint of the tiles of `layer`.
+    fn layer_tint(&self, layer: &SkyLayer) -> Color {
+        let tint = self
+            .weather
+            .tint()
+            .mix(&Color::BLACK, 1.0 - layer.brightness);
+        self.daylight.light(tint)
+    }
+
+    /// What the tiles are picked for.
+    fn fill(&self) -> SkyFill {
+        SkyFill {
+            weather: self.weather.current,
+            phase: self.daylight.phase,
+            cover: (self.weather.blend(Weather::cloud_cover) * SKY_COVER_STEPS).round() as u32,
+        }
+    }
+
+    /// How the tile at the axial `pos` of `layer` looks.
+    fn tile(
+        &self,
+        noise: &SkyNoise,
+        pos: IVec2,
+        layer: &SkyLayer,
+    ) -> (TileTextureIndex, TileVisible) {
+        let weather = &self.weather;
+        let phase = self.daylight.phase;
+        let density = noise
+            .0
+            .fractal(axial_to_world(pos) / SKY_CLOUD_SIZE, SKY_NOISE_OCTAVES);
+        let density = ((density - 0.5) * SKY_NOISE_CONTRAST + 0.5).clamp(0.0, 1.0);
+
+        if layer.solid {
+            return (
+                TileTextureIndex(weather.current.variant(phase, density)),
+                TileVisible(true),
+            );
+        }
+
+        // Only the densest parts are clouds, which are thicker towards their middles.
+        let clear = 1.0 - weather.blend(Weather::cloud_cover);
+        let thickness = ((density - clear) / (1.0 - clear)).clamp(0.0, 1.0);
+        (
+            TileTextureIndex(weather.current.variant(phase, thickness)),
+            TileVisible(density > clear),
+        )
+    }
 }
 
-/// Spawns the sky fitting the screen (to an extent).
-fn spawn_sky(mut commands: Commands, asset_server: Res<AssetServer>, mut rng: ResMut<SkyRand>) {
-    let texture_handle: Handle<Image> = asset_server.load(TILE_ASSET_LOAD_PATH);
+/// The position of the tile at the axial `pos`, relative to the origin of its layer.
+fn axial_to_world(pos: IVec2) -> Vec2 {
+    let pos = pos.as_vec2();
+    Vec2::new(TILE_SIZE.x * (pos.x + pos.y / 2.0), SKY_ROW_HEIGHT * pos.y)
+}
+
+/// The inverse of [`axial_to_world`], in fractions of a tile.
+fn world_to_axial(pos: Vec2) -> Vec2 {
+    let r = pos.y / SKY_ROW_HEIGHT;
+    Vec2::new(pos.x / TILE_SIZE.x - r / 2.0, r)
+}
+
+/// The part of the world the [`MainCamera`] sees.
+fn camera_view(transform: &Transform, projection: &Projection) -> Rect {
+    let Projection::Orthographic(projection2d) = projection else {
+        unreachable!("Only Orthographic Projection is supported!");
+    };
+    let area = projection2d.area;
+    Rect {
+        min: area.min + transform.translation.xy(),
+        max: area.max + transform.translation.xy(),
+    }
+}
 
+/// The chunks of a layer covering `view`, relative to the origin of the layer,
+/// as the first chunk and how many chunks across and up.
+fn chunks_covering(view: Rect) -> (IVec2, IVec2) {
+    // Tiles are drawn around their centers, so a tile of margin keeps their edges covered.
+    let view = view.inflate(TILE_SIZE.x.max(TILE_SIZE.y));
+    let corners = [
+        view.min,
+        Vec2::new(view.max.x, view.min.y),
+        Vec2::new(view.min.x, view.max.y),
+        view.max,
+    ]
+    .map(world_to_axial);
+    let min = corners.into_iter().fold(Vec2::MAX, Vec2::min);
+    let max = corners.into_iter().fold(Vec2::MIN, Vec2::max);
+
+    let chunk = SKY_CHUNK_SIZE as f32;
+    let first = (min / chunk).floor().as_ivec2();
+    // Sized from how big the view is rather than where, so the ring
+    // stays the same size as the view moves across it.
+    let size = ((max - min) / chunk).ceil().as_ivec2() + IVec2::ONE;
+    (first, size)
+}
+
+/// Whether the [`MainCamera`] may see more or less of the sky, from zooming
+/// or the size of what it draws to changing.
+fn camera_view_changed(camera: Query<(), (With<MainCamera>, Changed<Projection>)>) -> bool {
+    !camera.is_empty()
+}
+
+/// Spawns the chunk at `coord` of the layer `layer_entity`.
+fn spawn_chunk(
+    commands: &mut Commands,
+    layer_entity: Entity,
+    texture: &Handle<Image>,
+    coord: IVec2,
+    layer: &SkyLayer,
+    noise: &SkyNoise,
+    sky: &SkyConditions,
+) -> Entity {
     let tilemap_entity = commands.spawn_empty().id();
-    let mut tile_storage = TileStorage::empty(SKY_MAP_SIZE);
+    let mut tile_storage = TileStorage::empty(SKY_CHUNK_MAP_SIZE);
+    let tint = sky.layer_tint(layer);
 
     commands.entity(tilemap_entity).with_children(|parent| {
-        for x in 0..SKY_MAP_SIZE.x {
-            for y in 0..SKY_MAP_SIZE.y {
+        for x in 0..SKY_CHUNK_MAP_SIZE.x {
+            for y in 0..SKY_CHUNK_MAP_SIZE.y {
                 let tile_pos = TilePos { x, y };
+                let pos = coord * SKY_CHUNK_SIZE as i32 + IVec2::new(x as i32, y as i32);
+                let (texture_index, visible) = sky.tile(noise, pos, layer);
                 let id = parent
                     .spawn((
                         SkyTile,
                         TileBundle {
                             position: tile_pos,
                             tilemap_id: TilemapId(tilemap_entity),
-                            texture_index: TileTextureIndex(rng.0.random_range(SKY_TILE_VARIENTS)),
+                            texture_index,
+                            visible,
+                            color: TileColor(tint),
                             ..Default::default()
                         },
                     ))
@@ -77,115 +546,305 @@ fn spawn_sky(mut commands: Commands, asset_server: Res<AssetServer>, mut rng: Re
 
     commands.entity(tilemap_entity).insert((
         SkyTileMap,
+        SkyChunk { coord },
+        ChildOf(layer_entity),
         TilemapBundle {
             grid_size: TILE_SIZE.into(),
             map_type: TilemapType::Hexagon(HexCoordSystem::Row),
-            size: SKY_MAP_SIZE,
+            size: SKY_CHUNK_MAP_SIZE,
             storage: tile_storage,
-            texture: TilemapTexture::Single(texture_handle),
+            texture: TilemapTexture::Single(texture.clone()),
             tile_size: TILE_SIZE,
-            anchor: TilemapAnchor::Center,
-            transform: Transform::from_xyz(0., 0., SKY_TILE_LAYER),
+            transform: Transform::from_translation(
+                axial_to_world(coord * SKY_CHUNK_SIZE as i32).extend(0.),
+            ),
             ..Default::default()
         },
     ));
 
-    commands.insert_resource(SkySettings {
-        speed: Vec2::new(-5.0, -2.0),
-    });
+    tilemap_entity
 }
 
-/// Moves the sky with an illusion that it is indefinite.
-///
-/// This system
-///
-fn sky_movement(
+/// Spawns the [`SkyLayer`]s, whose chunks are spawned once the camera's view is known.
+fn spawn_sky(mut commands: Commands, sky: SkyConditions, seed: Res<WorldSeed>) {
+    for (index, layer) in SKY_LAYERS.iter().enumerate() {
+        let depth = (SKY_LAYERS.len() - 1 - index) as f32 * SKY_LAYER_SPACING;
+        commands.spawn((
+            *layer,
+            SkyNoise::new(*seed, index),
+            SkyScroll::default(),
+            SkyRing::default(),
+            SkyTint(sky.layer_tint(layer)),
+            sky.fill(),
+            Transform::from_xyz(0., 0., SKY_TILE_LAYER - depth),
+            Visibility::default(),
+        ));
+    }
+}
+
+/// Respawns the chunks of each [`SkyLayer`] when its ring no longer fits the camera's view.
+fn resize_sky(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    sky: SkyConditions,
+    camera: Single<(&Transform, &Projection), With<MainCamera>>,
+    mut layers: Query<
+        (Entity, &SkyLayer, &SkyNoise, &Transform, &mut SkyRing),
+        Without<MainCamera>,
+    >,
+) {
+    let (camera_transform, projection) = camera.into_inner();
+    let view = camera_view(camera_transform, projection);
+
+    for (layer_entity, layer, noise, transform, mut ring) in layers.iter_mut() {
+        let origin = transform.translation.xy();
+        let (first, size) =
+            chunks_covering(Rect::from_center_size(view.center() - origin, view.size()));
+
+        // Only shrinks once the ring is well over the size needed, so
+        // zoom punches don't respawn the sky back and forth.
+        let grow = size.cmpgt(ring.size).any();
+        let shrink = (size + IVec2::ONE).cmplt(ring.size).any();
+        if !grow && !shrink {
+            continue;
+        }
+
+        for chunk in ring.chunks.drain(..) {
+            commands.entity(chunk).despawn();
+        }
+
+        let texture: Handle<Image> = asset_server.load(TILE_ASSET_LOAD_PATH);
+        ring.size = size;
+        ring.chunks = vec![Entity::PLACEHOLDER; (size.x * size.y) as usize];
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let coord = first + IVec2::new(x, y);
+                let slot = coord.rem_euclid(size);
+                ring.chunks[(slot.y * size.x + slot.x) as usize] = spawn_chunk(
+                    &mut commands,
+                    layer_entity,
+                    &texture,
+                    coord,
+                    layer,
+                    noise,
+                    &sky,
+                );
+            }
+        }
+    }
+}
+
+/// Changes the weather once it has lasted long enough, or to the weather of the
+/// [`Forecast`] if it doesn't change, and blends into the new weather.
+fn update_weather(
     time: Res<Time>,
-    sky_movement: ResMut<SkySettings>,
+    settings: Res<SkySettings>,
     mut rng: ResMut<SkyRand>,
-    tilemap: Single<(&TileStorage, &TilemapSize, &mut Transform), With<SkyTileMap>>,
-    mut tile_query: Query<&mut TileTextureIndex, With<SkyTile>>,
+    mut weather: ResMut<SkyWeather>,
 ) {
-    let (tile_storage, map_size, mut transform) = tilemap.into_inner();
+    let delta = time.delta_secs();
 
-    let map_size: IVec2 = IVec2::new(map_size.x as i32, map_size.y as i32);
+    if weather.transition < 1.0 {
+        weather.transition = (weather.transition + delta / WEATHER_TRANSITION).min(1.0);
+    }
 
-    let new_translation = AXIAL_TRANSLATION_MATRIX * sky_movement.speed * time.delta_secs()
-        + transform.translation.xy();
+    if let Some(forecast) = settings.forecast.weather() {
+        weather.set(forecast);
+        return;
+    }
+
+    weather.remaining -= delta;
+    if weather.remaining <= 0.0 {
+        weather.remaining = rng.0.random_range(WEATHER_DURATION);
+        let others: Vec<Weather> = Weather::ALL
+            .into_iter()
+            .filter(|other| *other != weather.current)
+            .collect();
+        let next = others[rng.0.random_range(0..others.len())];
+        weather.set(next);
+    }
+}
 
-    let tile_diff = (new_translation / SKY_TILE_SIZE_LOOP_THRESHOLD)
-        .trunc()
-        .as_ivec2();
+/// Colors the tiles of each [`SkyLayer`] by the weather and the daylight, when they change.
+fn tint_sky(
+    sky: SkyConditions,
+    mut layers: Query<(&SkyLayer, &SkyRing, &mut SkyTint)>,
+    chunks: Query<&TileStorage, With<SkyTileMap>>,
+    mut tile_query: Query<&mut TileColor, With<SkyTile>>,
+) {
+    for (layer, ring, mut applied) in layers.iter_mut() {
+        let tint = SkyTint(sky.layer_tint(layer));
+        if *applied == tint {
+            continue;
+        }
 
-    // only translate by the sky by the amount that was less than a whole tile.
-    let new_translation = new_translation - tile_diff.as_vec2() * SKY_TILE_SIZE_LOOP_THRESHOLD;
+        for tile_storage in chunks.iter_many(&ring.chunks) {
+            for tile_entity in tile_storage.iter().flatten() {
+                if let Ok(mut color) = tile_query.get_mut(*tile_entity) {
+                    color.0 = tint.0;
+                }
+            }
+        }
+        *applied = tint;
+    }
+}
 
-    transform.translation = new_translation.extend(transform.translation.z);
+/// Picks the tiles of each [`SkyLayer`] again when the sky changes, so the
+/// clouds change where they are rather than only as new ones scroll in.
+fn refill_sky(
+    sky: SkyConditions,
+    mut layers: Query<(&SkyLayer, &SkyNoise, &SkyRing, &mut SkyFill)>,
+    chunks: Query<(&TileStorage, &SkyChunk), With<SkyTileMap>>,
+    mut tile_query: Query<(&mut TileTextureIndex, &mut TileVisible), With<SkyTile>>,
+) {
+    let fill = sky.fill();
+    for (layer, noise, ring, mut filled) in layers.iter_mut() {
+        if *filled == fill {
+            continue;
+        }
 
-    if tile_diff == IVec2::ZERO {
-        return;
+        for (tile_storage, chunk) in chunks.iter_many(&ring.chunks) {
+            refill_chunk(
+                tile_storage,
+                chunk.coord,
+                layer,
+                noise,
+                &sky,
+                &mut tile_query,
+            );
+        }
+        *filled = fill;
     }
+}
 
-    let flip_x = tile_diff.x > 0;
-    let flip_y = tile_diff.y > 0;
+/// Moves the sky with an illusion that it is indefinite.
+///
+/// Each [`SkyLayer`] follows the camera by its parallax while the clouds drift across it.
+/// The chunks of its ring that have fallen out of the camera's view are moved to the
+/// side coming into view, and refilled.
+fn sky_movement(
+    time: Res<Time>,
+    sky_movement: Res<SkySettings>,
+    sky: SkyConditions,
+    display: Res<DisplaySettings>,
+    camera: Single<(&Transform, &Projection), With<MainCamera>>,
+    mut layers: Query<
+        (
+            &SkyLayer,
+            &SkyNoise,
+            &SkyRing,
+            &mut SkyScroll,
+            &mut Transform,
+        ),
+        Without<MainCamera>,
+    >,
+    mut chunks: Query<
+        (&TileStorage, &mut SkyChunk, &mut Transform),
+        (With<SkyTileMap>, Without<SkyLayer>, Without<MainCamera>),
+    >,
+    mut tile_query: Query<(&mut TileTextureIndex, &mut TileVisible), With<SkyTile>>,
+) {
+    let (camera_transform, projection) = camera.into_inner();
+    let camera = camera_transform.translation.xy();
+    let view = camera_view(camera_transform, projection);
+    let drift = AXIAL_TRANSLATION_MATRIX
+        * sky_movement.speed
+        * sky.weather.blend(Weather::drift)
+        * time.delta_secs();
 
-    for y in 0..map_size.y {
-        let y = flip_y.then_some(map_size.y - y - 1).unwrap_or(y);
-        for x in 0..map_size.x {
-            let x = flip_x.then_some(map_size.x - x - 1).unwrap_or(x);
+    for (layer, noise, ring, mut scroll, mut transform) in layers.iter_mut() {
+        scroll.0 += drift * layer.drift;
 
-            let old_pos = IVec2 { x, y };
+        let mut origin = scroll.0 + camera * layer.parallax;
+        if display.pixel_perfect {
+            origin = origin.round();
+        }
+        transform.translation = origin.extend(transform.translation.z);
 
-            // for the hexagons to align with where you started, they have
-            // to move 1.5 hexes up or 1 hex to the right.
-            // This does the 1.5 hexes up adjustment to turn the
-            // hex distance into square distance used by the position.
-            let adjusted_diff =
-                (Mat2::from_cols_array(&[1., 0., -1., 2.]) * tile_diff.as_vec2()).as_ivec2();
+        let (first, _) =
+            chunks_covering(Rect::from_center_size(view.center() - origin, view.size()));
 
-            let replace_pos = old_pos + adjusted_diff;
-            let new_pos = old_pos - adjusted_diff;
+        for (index, chunk_entity) in ring.chunks.iter().enumerate() {
+            let slot = IVec2::new(index as i32 % ring.size.x, index as i32 / ring.size.x);
+            // The chunk of the view that goes in this slot of the ring.
+            let coord = first + (slot - first).rem_euclid(ring.size);
 
-            let Some(curr_tile_entity) = tile_storage.get(&old_pos.as_uvec2().into()) else {
-                warn!("Failed to find sky tile entity at position ({x}, {y})");
+            let Ok((tile_storage, mut chunk, mut chunk_transform)) = chunks.get_mut(*chunk_entity)
+            else {
+                warn!("Failed to find sky chunk {chunk_entity}");
                 continue;
             };
+            if chunk.coord == coord {
+                continue;
+            }
 
-            if replace_pos.cmpge(IVec2::ZERO).all() && replace_pos.cmplt(map_size).all() {
-                // move the texture along the `tile_diff` vector
-
-                let Some(new_tile_entity) = tile_storage.get(&replace_pos.as_uvec2().into()) else {
-                    warn!("Failed to find new tile at pos {replace_pos}");
-                    continue;
-                };
+            chunk.coord = coord;
+            chunk_transform.translation =
+                axial_to_world(coord * SKY_CHUNK_SIZE as i32).extend(chunk_transform.translation.z);
+            refill_chunk(tile_storage, coord, layer, noise, &sky, &mut tile_query);
+        }
+    }
+}
 
-                let curr_tile_texture = match tile_query.get(curr_tile_entity).and_then(|t| Ok(*t))
-                {
-                    Ok(curr_tile_texture) => curr_tile_texture,
-                    Err(err) => {
-                        warn!("Failed to find base sky tile at {old_pos} with {err}");
-                        continue;
-                    }
-                };
+/// Fills the chunk at `coord` with its tiles, such as when it is moved
+/// to the other side of its [`SkyRing`] or the sky changes.
+fn refill_chunk(
+    tile_storage: &TileStorage,
+    coord: IVec2,
+    layer: &SkyLayer,
+    noise: &SkyNoise,
+    sky: &SkyConditions,
+    tile_query: &mut Query<(&mut TileTextureIndex, &mut TileVisible), With<SkyTile>>,
+) {
+    for x in 0..SKY_CHUNK_MAP_SIZE.x {
+        for y in 0..SKY_CHUNK_MAP_SIZE.y {
+            let tile_pos = TilePos { x, y };
+            let Some(tile_entity) = tile_storage.get(&tile_pos) else {
+                warn!("Failed to find sky tile entity at position ({x}, {y})");
+                continue;
+            };
 
-                match tile_query.get_mut(new_tile_entity) {
-                    Ok(mut new_tile_texture) => *new_tile_texture = curr_tile_texture,
-                    Err(err) => {
-                        warn!("Failed to find to be replaced sky tile at {replace_pos} with {err}");
-                        continue;
-                    }
+            let pos = coord * SKY_CHUNK_SIZE as i32 + IVec2::new(x as i32, y as i32);
+            match tile_query.get_mut(tile_entity) {
+                Ok((mut texture, mut visible)) => {
+                    (*texture, *visible) = sky.tile(noise, pos, layer);
                 }
+                Err(err) => warn!("Failed to get sky tile {tile_entity} with {err}"),
             }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
 
-            if new_pos.cmplt(IVec2::ZERO).any() || new_pos.cmpge(map_size).any() {
-                match tile_query.get_mut(curr_tile_entity) {
-                    Ok(mut curr_tile_texture) => {
-                        let tile_idx = rng.0.random_range(SKY_TILE_VARIENTS);
-                        *curr_tile_texture = TileTextureIndex(tile_idx);
+    /// How the tiles around the origin of every layer look for `seed`.
+    fn tiles(seed: u64) -> Vec<(u32, bool)> {
+        let mut world = World::new();
+        world.init_resource::<SkyWeather>();
+        world.init_resource::<Daylight>();
+        world
+            .run_system_once(move |sky: SkyConditions| {
+                let mut tiles = Vec::new();
+                for (index, layer) in SKY_LAYERS.iter().enumerate() {
+                    let noise = SkyNoise::new(WorldSeed(seed), index);
+                    for x in -16..16 {
+                        for y in -16..16 {
+                            let (texture, visible) = sky.tile(&noise, IVec2::new(x, y), layer);
+                            tiles.push((texture.0, visible.0));
+                        }
                     }
-                    Err(err) => warn!("Failed to get current tile at {new_pos} with {err}"),
-                };
-            }
-        }
+                }
+                tiles
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn the_same_seed_draws_the_same_sky() {
+        assert_eq!(tiles(1234), tiles(1234));
+        assert_ne!(tiles(1234), tiles(4321));
     }
 }
@@ -0,0 +1,481 @@
+//! The volume channels, and playing music and sound effects through them.
+//!
+//! Send [`PlaySound`] to play a sound effect, and [`PlayMusic`]
+//! to change the music that is looping in the background.
+use crate::database::SetKvError;
+use crate::prelude::*;
+use bevy::{audio::Volume, prelude::*, window::WindowFocused};
+use core::time::Duration;
+use std::collections::HashMap;
+
+const MASTER_VOLUME_DB_KEY: &str = "master_volume";
+const MUSIC_VOLUME_DB_KEY: &str = "music_volume";
+const SFX_VOLUME_DB_KEY: &str = "sfx_volume";
+const UI_VOLUME_DB_KEY: &str = "ui_volume";
+const MUTE_ON_FOCUS_LOSS_DB_KEY: &str = "mute_on_focus_loss";
+
+const DEFAULT_MASTER_VOLUME: f32 = 1.0;
+const DEFAULT_MUSIC_VOLUME: f32 = 0.7;
+const DEFAULT_SFX_VOLUME: f32 = 0.8;
+const DEFAULT_UI_VOLUME: f32 = 0.8;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlaySound>()
+            .add_event::<PlayMusic>()
+            .init_resource::<WindowFocus>()
+            .init_resource::<Music>()
+            .add_systems(Startup, setup_sound)
+            .add_systems(OnEnter(GameState::Menu), start_music(MusicTrack::Menu))
+            .add_systems(
+                OnEnter(GameState::Game),
+                (
+                    play_sound(SoundEffect::GameStart),
+                    start_music(MusicTrack::Game),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    sound_settings_sync.run_if(
+                        resource_changed::<SoundSettings>.and(not(resource_added::<SoundSettings>)),
+                    ),
+                    track_window_focus,
+                    button_click_sound,
+                    (play_sounds, (change_music, play_music).chain()),
+                    apply_volume.run_if(
+                        resource_changed::<SoundSettings>.or(resource_changed::<WindowFocus>),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The volume channels, each of which is scaled by [`AudioChannel::Master`].
+#[derive(Component, Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Component, Debug, Hash, PartialEq, Clone)]
+pub enum AudioChannel {
+    Master,
+    Music,
+    Sfx,
+    Ui,
+}
+
+impl AudioChannel {
+    pub const ALL: [Self; 4] = [Self::Master, Self::Music, Self::Sfx, Self::Ui];
+
+    fn db_key(self) -> &'static str {
+        match self {
+            AudioChannel::Master => MASTER_VOLUME_DB_KEY,
+            AudioChannel::Music => MUSIC_VOLUME_DB_KEY,
+            AudioChannel::Sfx => SFX_VOLUME_DB_KEY,
+            AudioChannel::Ui => UI_VOLUME_DB_KEY,
+        }
+    }
+}
+
+impl From<AudioChannel> for Localized {
+    fn from(channel: AudioChannel) -> Self {
+        Localized::new(match channel {
+            AudioChannel::Master => "audio_channel.master",
+            AudioChannel::Music => "audio_channel.music",
+            AudioChannel::Sfx => "audio_channel.sfx",
+            AudioChannel::Ui => "audio_channel.ui",
+        })
+    }
+}
+
+/// The volume of each channel, from 0 to 1.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Debug, Clone, PartialEq)]
+pub struct SoundSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    pub ui: f32,
+    /// Whether to silence the game while the window isn't focused.
+    pub mute_on_focus_loss: bool,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            master: DEFAULT_MASTER_VOLUME,
+            music: DEFAULT_MUSIC_VOLUME,
+            sfx: DEFAULT_SFX_VOLUME,
+            ui: DEFAULT_UI_VOLUME,
+            mute_on_focus_loss: true,
+        }
+    }
+}
+
+impl SoundSettings {
+    pub fn get(&self, channel: AudioChannel) -> f32 {
+        match channel {
+            AudioChannel::Master => self.master,
+            AudioChannel::Music => self.music,
+            AudioChannel::Sfx => self.sfx,
+            AudioChannel::Ui => self.ui,
+        }
+    }
+
+    pub fn get_mut(&mut self, channel: AudioChannel) -> &mut f32 {
+        match channel {
+            AudioChannel::Master => &mut self.master,
+            AudioChannel::Music => &mut self.music,
+            AudioChannel::Sfx => &mut self.sfx,
+            AudioChannel::Ui => &mut self.ui,
+        }
+    }
+
+    /// The volume a sound on `channel` should be played at.
+    pub fn volume(&self, channel: AudioChannel) -> f32 {
+        match channel {
+            AudioChannel::Master => self.master,
+            _ => self.master * self.get(channel),
+        }
+    }
+
+    /// Writes only the settings that differ from `previous`,
+    /// as a slider changes the settings on every step.
+    fn changes_to_database(&self, previous: &Self, database: &Database) -> Result<(), SetKvError> {
+        for channel in AudioChannel::ALL {
+            if self.get(channel) != previous.get(channel) {
+                database.set_kv(channel.db_key(), self.get(channel))?;
+            }
+        }
+        if self.mute_on_focus_loss != previous.mute_on_focus_loss {
+            database.set_kv(MUTE_ON_FOCUS_LOSS_DB_KEY, self.mute_on_focus_loss)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromDatabase for SoundSettings {
+    fn from_database(database: &Database) -> Self {
+        let default = Self::default();
+        let volume = |key, default: f32| {
+            let volume: f32 = database.get_kv_or_default(key, default);
+            volume.clamp(0.0, 1.0)
+        };
+
+        Self {
+            master: volume(AudioChannel::Master.db_key(), default.master),
+            music: volume(AudioChannel::Music.db_key(), default.music),
+            sfx: volume(AudioChannel::Sfx.db_key(), default.sfx),
+            ui: volume(AudioChannel::Ui.db_key(), default.ui),
+            mute_on_focus_loss: database
+                .get_kv_or_default(MUTE_ON_FOCUS_LOSS_DB_KEY, default.mute_on_focus_loss),
+        }
+    }
+}
+
+impl ToDatabase for SoundSettings {
+    fn to_database(&self, database: &Database) -> Result<(), SetKvError> {
+        for channel in AudioChannel::ALL {
+            database.set_kv(channel.db_key(), self.get(channel))?;
+        }
+        database.set_kv(MUTE_ON_FOCUS_LOSS_DB_KEY, self.mute_on_focus_loss)?;
+        Ok(())
+    }
+}
+
+/// Whether any window has focus, used to mute the game when it doesn't.
+#[derive(Resource, Debug)]
+struct WindowFocus(bool);
+
+impl Default for WindowFocus {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// The sound effects in the game.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Debug, Hash, PartialEq, Clone)]
+pub enum SoundEffect {
+    /// A button was clicked.
+    UiClick,
+    /// The menu focus moved to another button.
+    UiMove,
+    /// A game was started.
+    GameStart,
+}
+
+impl SoundEffect {
+    pub const ALL: [Self; 3] = [Self::UiClick, Self::UiMove, Self::GameStart];
+
+    pub fn channel(self) -> AudioChannel {
+        match self {
+            Self::UiClick | Self::UiMove => AudioChannel::Ui,
+            Self::GameStart => AudioChannel::Sfx,
+        }
+    }
+
+    /// Until there are recorded sounds, each effect is a short tone.
+    ///
+    /// returns: (Frequency, Duration)
+    fn tone(self) -> (f32, Duration) {
+        match self {
+            Self::UiClick => (880.0, Duration::from_millis(40)),
+            Self::UiMove => (660.0, Duration::from_millis(25)),
+            Self::GameStart => (523.25, Duration::from_millis(150)),
+        }
+    }
+}
+
+/// Plays a sound effect once.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlaySound(pub SoundEffect);
+
+#[derive(Resource)]
+struct SoundEffects(HashMap<SoundEffect, Handle<Pitch>>);
+
+/// The background music of each part of the game.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect)]
+#[reflect(Debug, Hash, PartialEq, Clone)]
+pub enum MusicTrack {
+    Menu,
+    Game,
+}
+
+impl MusicTrack {
+    pub const ALL: [Self; 2] = [Self::Menu, Self::Game];
+
+    /// Until there is recorded music, each track is a looping melody of tones.
+    ///
+    /// returns: the frequency and milliseconds of each note, where a frequency of `0` is a rest
+    fn melody(self) -> &'static [(f32, u64)] {
+        match self {
+            Self::Menu => &[
+                (261.63, 400),
+                (329.63, 400),
+                (392.0, 400),
+                (329.63, 400),
+                (349.23, 400),
+                (440.0, 400),
+                (392.0, 800),
+                (0.0, 400),
+            ],
+            Self::Game => &[
+                (220.0, 300),
+                (261.63, 300),
+                (329.63, 600),
+                (293.66, 300),
+                (261.63, 300),
+                (246.94, 600),
+                (220.0, 900),
+                (0.0, 600),
+            ],
+        }
+    }
+}
+
+/// Replaces the looping background music, or stops it with `None`.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayMusic(pub Option<MusicTrack>);
+
+/// The tone of each note of a [`MusicTrack`] with how long it lasts, with `None` for the rests.
+type Melody = Vec<(Option<Handle<Pitch>>, Duration)>;
+
+#[derive(Resource)]
+struct MusicTracks(HashMap<MusicTrack, Melody>);
+
+/// The background music playing, and how long until its next note.
+#[derive(Resource, Default)]
+struct Music {
+    track: Option<MusicTrack>,
+    /// The next note of the track to play.
+    note: usize,
+    next_note: Duration,
+}
+
+/// Marks the notes of the background music, which are stopped when it changes.
+#[derive(Component)]
+struct MusicNote;
+
+fn setup_sound(
+    mut commands: Commands,
+    database: Res<Database>,
+    mut pitches: ResMut<Assets<Pitch>>,
+) {
+    commands.insert_resource(SoundSettings::from_database(&database));
+    commands.insert_resource(SoundEffects(
+        SoundEffect::ALL
+            .into_iter()
+            .map(|effect| {
+                let (frequency, duration) = effect.tone();
+                (effect, pitches.add(Pitch::new(frequency, duration)))
+            })
+            .collect(),
+    ));
+    commands.insert_resource(MusicTracks(
+        MusicTrack::ALL
+            .into_iter()
+            .map(|track| {
+                let notes = track
+                    .melody()
+                    .iter()
+                    .map(|&(frequency, millis)| {
+                        let duration = Duration::from_millis(millis);
+                        let pitch =
+                            (frequency > 0.0).then(|| pitches.add(Pitch::new(frequency, duration)));
+                        (pitch, duration)
+                    })
+                    .collect();
+                (track, notes)
+            })
+            .collect(),
+    ));
+}
+
+fn sound_settings_sync(
+    database: Res<Database>,
+    settings: Res<SoundSettings>,
+    mut synced: Local<Option<SoundSettings>>,
+) {
+    let previous = synced.unwrap_or_else(|| SoundSettings::from_database(&database));
+    match settings.changes_to_database(&previous, &database) {
+        Ok(()) => *synced = Some(*settings),
+        Err(err) => warn!("Failed to sync sound settings to database with: {err}"),
+    }
+}
+
+/// Returns a system that plays `effect`, for playing sounds on state transitions.
+pub fn play_sound(effect: SoundEffect) -> impl FnMut(EventWriter<PlaySound>) {
+    move |mut sounds: EventWriter<PlaySound>| {
+        sounds.write(PlaySound(effect));
+    }
+}
+
+/// Returns a system that changes the music to `track`, for playing music on state transitions.
+pub fn start_music(track: MusicTrack) -> impl FnMut(EventWriter<PlayMusic>) {
+    move |mut music: EventWriter<PlayMusic>| {
+        music.write(PlayMusic(Some(track)));
+    }
+}
+
+fn track_window_focus(mut focus: ResMut<WindowFocus>, mut events: EventReader<WindowFocused>) {
+    if let Some(event) = events
+        .read()
+        .last()
+        .filter(|event| event.focused != focus.0)
+    {
+        focus.0 = event.focused;
+    }
+}
+
+fn button_click_sound(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    if buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        sounds.write(PlaySound(SoundEffect::UiClick));
+    }
+}
+
+/// The volume to play a sound on `channel` at right now.
+fn current_volume(settings: &SoundSettings, focus: &WindowFocus, channel: AudioChannel) -> Volume {
+    if settings.mute_on_focus_loss && !focus.0 {
+        Volume::Linear(0.0)
+    } else {
+        Volume::Linear(settings.volume(channel))
+    }
+}
+
+fn play_sounds(
+    mut commands: Commands,
+    mut events: EventReader<PlaySound>,
+    effects: Res<SoundEffects>,
+    settings: Res<SoundSettings>,
+    focus: Res<WindowFocus>,
+) {
+    for PlaySound(effect) in events.read() {
+        let Some(handle) = effects.0.get(effect) else {
+            continue;
+        };
+
+        let channel = effect.channel();
+        commands.spawn((
+            AudioPlayer::<Pitch>(handle.clone()),
+            PlaybackSettings::DESPAWN.with_volume(current_volume(&settings, &focus, channel)),
+            channel,
+        ));
+    }
+}
+
+/// Updates the volume of everything that is already playing.
+fn apply_volume(
+    settings: Res<SoundSettings>,
+    focus: Res<WindowFocus>,
+    mut sinks: Query<(&mut AudioSink, &AudioChannel)>,
+) {
+    for (mut sink, channel) in sinks.iter_mut() {
+        sink.set_volume(current_volume(&settings, &focus, *channel));
+    }
+}
+
+/// Changes the music on [`PlayMusic`], stopping the notes of the previous music.
+fn change_music(
+    mut commands: Commands,
+    mut events: EventReader<PlayMusic>,
+    mut music: ResMut<Music>,
+    notes: Query<Entity, With<MusicNote>>,
+) {
+    let changed = events
+        .read()
+        .last()
+        .map(|PlayMusic(track)| *track)
+        .filter(|track| *track != music.track);
+    if let Some(track) = changed {
+        for note in notes.iter() {
+            commands.entity(note).despawn();
+        }
+        *music = Music { track, ..default() };
+    }
+}
+
+/// Plays each note of the music in turn on [`AudioChannel::Music`],
+/// which keeps going while the game is paused.
+fn play_music(
+    mut commands: Commands,
+    mut music: ResMut<Music>,
+    tracks: Res<MusicTracks>,
+    settings: Res<SoundSettings>,
+    focus: Res<WindowFocus>,
+    time: Res<Time<Real>>,
+) {
+    let Some(melody) = music.track.and_then(|track| tracks.0.get(&track)) else {
+        return;
+    };
+    if melody.is_empty() {
+        return;
+    }
+
+    music.next_note = music.next_note.saturating_sub(time.delta());
+    if !music.next_note.is_zero() {
+        return;
+    }
+
+    let (pitch, duration) = &melody[music.note];
+    if let Some(pitch) = pitch {
+        commands.spawn((
+            MusicNote,
+            AudioPlayer::<Pitch>(pitch.clone()),
+            PlaybackSettings::DESPAWN.with_volume(current_volume(
+                &settings,
+                &focus,
+                AudioChannel::Music,
+            )),
+            AudioChannel::Music,
+        ));
+    }
+    music.note = (music.note + 1) % melody.len();
+    music.next_note = *duration;
+}
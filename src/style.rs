@@ -1,21 +1,20 @@
 use crate::controls::Input;
 use crate::embed_asset;
+use crate::glyphs::{GamepadGlyphs, GlyphEntry, GlyphSet};
 use crate::prelude::*;
+use crate::theme::{Theme, ThemeColor, ThemePresets};
+use crate::typography::{FontError, TextSize, load_font_file};
 use bevy::prelude::*;
+use std::path::PathBuf;
 
 const STYLE_DB_TABLE: &str = "Style";
 const BUTTON_SPRITE_IMAGE_PATH: &str = "embedded://assets/sprites/buttons.png";
 const BUTTON_GLYPH_SIZE: UVec2 = UVec2::new(32, 36);
-const BUTTON_GLYPH_TEXT_COLOR: Color = Color::BLACK;
+pub const BUTTON_GLYPH_TEXT_COLOR: Color = Color::BLACK;
 
 const DEFAULT_FONT_PATH: &str = "embedded://assets/fonts/Ithaca/Ithaca-LVB75.ttf";
-const DEFAULT_TEXT_COLOR: Color = Color::srgb_u8(0xe0, 0xde, 0xf4);
-const DEFAULT_BACKGROUND_COLOR: Color = Color::srgba_u8(0x26, 0x23, 0x3a, 0xaa);
-const DEFAULT_TITLE_COLOR: Color = Color::srgb_u8(0x26, 0x23, 0x3a);
-const DEFAULT_BUTTON_COLOR: Color = Color::srgb_u8(0x26, 0x23, 0x3a);
-const DEFAULT_PRESSED_BUTTON_COLOR: Color = Color::srgb_u8(0x9c, 0xcf, 0xd8);
-const DEFAULT_HOVERED_BUTTON_COLOR: Color = Color::srgb_u8(0x1f, 0x1d, 0x2e);
-const DEFAULT_HOVERED_PRESSED_BUTTON_COLOR: Color = Color::srgb_u8(0x1f, 0x1d, 0x2e);
+const FONT_DB_KEY: &str = "font";
+const CUSTOM_FONT_DB_KEY: &str = "custom_font";
 
 pub struct StylePlugin;
 
@@ -23,10 +22,12 @@ impl Plugin for StylePlugin {
     fn build(&self, app: &mut App) {
         embed_asset!(app, "assets/fonts/Ithaca/Ithaca-LVB75.ttf");
 
-        app.add_systems(Startup, add_style).add_systems(
-            Update,
-            sync_to_database.run_if(resource_exists_and_changed::<Style>),
-        );
+        app.init_resource::<ThemePresets>()
+            .add_systems(Startup, add_style)
+            .add_systems(
+                Update,
+                (sync_to_database, restyle).run_if(resource_exists_and_changed::<Style>),
+            );
     }
 }
 
@@ -43,10 +44,85 @@ pub fn add_style(mut commands: Commands, database: Res<Database>, asset_server:
     ));
 }
 
+/// The part a node plays in the UI, which decides the colors it takes from the [`Style`].
+///
+/// Nodes with a role are restyled whenever the [`Style`] changes, so screens
+/// that are already open follow edits to the theme or font.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component, Debug, PartialEq)]
+pub enum StyleRole {
+    /// Large text, such as the name of the game.
+    Title,
+    Text,
+    /// Text warning of a problem, such as conflicting keybinds.
+    Warning,
+    /// The labels drawn on top of input glyphs.
+    GlyphLabel,
+    /// The background of a button, which the menus change with its state.
+    Button,
+    /// The part of a widget showing its value, such as the filled part of a slider.
+    Fill,
+    /// The translucent background behind menus.
+    Background,
+    /// The opaque background of dialogs shown over other menus.
+    Dialog,
+}
+
+impl StyleRole {
+    pub fn color(self, style: &Style) -> Color {
+        match self {
+            Self::Title => style.title_color,
+            Self::Text => style.text_color,
+            Self::Warning => style.conflict_button_color,
+            Self::GlyphLabel => BUTTON_GLYPH_TEXT_COLOR,
+            Self::Button => style.button_color,
+            Self::Fill => style.pressed_button_color,
+            Self::Background => style.background_color,
+            Self::Dialog => style.background_color.with_alpha(1.0),
+        }
+    }
+}
+
+/// Re-applies the [`Style`] to every node with a [`StyleRole`].
+///
+/// Text takes the font and color of its role and the size of its [`TextSize`], bordered nodes take the color as their border,
+/// and other nodes take it as their background, or as a tint when drawn with an image.
+pub fn restyle(
+    style: Res<Style>,
+    mut texts: Query<(&StyleRole, Option<&TextSize>, &mut TextFont, &mut TextColor)>,
+    mut borders: Query<(&StyleRole, &mut BorderColor)>,
+    mut nodes: Query<
+        (&StyleRole, &mut BackgroundColor, Option<&mut ImageNode>),
+        (Without<TextColor>, Without<BorderColor>),
+    >,
+) {
+    for (role, size, mut font, mut color) in texts.iter_mut() {
+        font.font = style.font.clone();
+        if let Some(size) = size {
+            font.font_size = size.px();
+        }
+        color.0 = role.color(&style);
+    }
+
+    for (role, mut border) in borders.iter_mut() {
+        border.0 = role.color(&style);
+    }
+
+    for (role, mut background, image) in nodes.iter_mut() {
+        match image {
+            Some(mut image) => image.color = role.color(&style),
+            None => background.0 = role.color(&style),
+        }
+    }
+}
+
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
 pub struct Style {
+    /// The font text is drawn with.
     pub font: Handle<Font>,
+    /// The font the player loaded from disk in place of the default, if any.
+    pub custom_font: Option<PathBuf>,
     icons: Icons,
 
     pub background_color: Color,
@@ -56,51 +132,103 @@ pub struct Style {
     pub pressed_button_color: Color,
     pub hovered_button_color: Color,
     pub hovered_pressed_button_color: Color,
+    /// The color of buttons that are in an invalid state, such as conflicting keybinds.
+    pub conflict_button_color: Color,
 }
 
 impl Style {
-    pub fn font(&self, font_size: f32) -> TextFont {
+    pub fn font(&self, size: TextSize) -> TextFont {
         TextFont {
             font: self.font.clone(),
-            font_size,
+            font_size: size.px(),
             ..default()
         }
     }
 
+    /// The font and color of text playing `role`, which follow the style when it changes.
+    pub fn text(
+        &self,
+        role: StyleRole,
+        size: TextSize,
+    ) -> (TextFont, TextColor, StyleRole, TextSize) {
+        (self.font(size), TextColor(role.color(self)), role, size)
+    }
+
+    /// Draws text with the font at `path` instead of the default.
+    pub fn set_custom_font(
+        &mut self,
+        path: PathBuf,
+        asset_server: &AssetServer,
+    ) -> Result<(), FontError> {
+        self.font = asset_server.add(load_font_file(&path)?);
+        self.custom_font = Some(path);
+        Ok(())
+    }
+
+    /// Goes back to drawing text with the default font.
+    pub fn reset_font(&mut self, asset_server: &AssetServer) {
+        self.font = asset_server.load(DEFAULT_FONT_PATH);
+        self.custom_font = None;
+    }
+
+    /// The background of a node playing `role`, which follows the style when it changes.
+    pub fn background(&self, role: StyleRole) -> (BackgroundColor, StyleRole) {
+        (BackgroundColor(role.color(self)), role)
+    }
+
     /// Spawns Node(s) representing inputs, using glyphs where possible.
-    pub fn display_keybind(&self, builder: &mut ChildSpawnerCommands<'_>, keybind: &Keybind) {
-        let Keybind(control, key) = keybind;
-        match key {
-            [Some(a), Some(b)] => {
-                builder
-                    .spawn(Node { ..default() })
-                    .with_children(move |builder| {
-                        self.display_input(builder, a);
-                        builder.spawn((
-                            Text::new("/"),
-                            self.font(32.0),
-                            TextColor(self.text_color),
-                            Label,
-                            Pickable::IGNORE,
-                        ));
-                        self.display_input(builder, b);
-                    });
-            }
-            [Some(a), None] | [None, Some(a)] => self.display_input(builder, a),
-            [None, None] => {
-                builder.spawn((
-                    Text::new(format!("{control} Not Bound")),
-                    self.font(32.0),
-                    TextColor(self.text_color),
-                    Label,
-                    Pickable::IGNORE,
-                ));
-            }
-        }
+    pub fn display_keybind(
+        &self,
+        builder: &mut ChildSpawnerCommands<'_>,
+        glyphs: &GamepadGlyphs,
+        keybind: &Keybind,
+    ) {
+        let Keybind(control, keys) = keybind;
+        let mut keys = keys.iter().flatten();
+
+        let Some(first) = keys.next() else {
+            builder.spawn((
+                localized(Localized::new("prompt.not_bound").with("control", *control)),
+                self.text(StyleRole::Text, TextSize::Glyph),
+                Label,
+                Pickable::IGNORE,
+            ));
+            return;
+        };
+
+        builder
+            .spawn((Node::default(), Pickable::IGNORE))
+            .with_children(move |builder| {
+                self.display_input(builder, glyphs, first);
+                for key in keys {
+                    builder.spawn((
+                        Text::new("/"),
+                        self.text(StyleRole::Text, TextSize::Glyph),
+                        Label,
+                        Pickable::IGNORE,
+                    ));
+                    self.display_input(builder, glyphs, key);
+                }
+            });
     }
 
     /// Spawns Node(s) representing inputs, using glyphs where possible.
-    pub fn display_input(&self, builder: &mut ChildSpawnerCommands<'_>, input: &Input) {
+    pub fn display_input(
+        &self,
+        builder: &mut ChildSpawnerCommands<'_>,
+        glyphs: &GamepadGlyphs,
+        input: &Input,
+    ) {
+        if let Input::Gamepad(button) = input {
+            if let Some((set, entry)) = glyphs
+                .active_set()
+                .and_then(|set| set.glyph(*button).map(|entry| (set, entry)))
+            {
+                self.display_gamepad_glyph(builder, set, entry);
+                return;
+            }
+        }
+
         match input_glyph_info(input) {
             Some((index, size, display_text)) => {
                 if display_text {
@@ -119,9 +247,8 @@ impl Style {
                         self.icons.to_node(index),
                         Pickable::IGNORE,
                         children![(
-                            Text::new(input.to_string()),
-                            TextColor(BUTTON_GLYPH_TEXT_COLOR),
-                            self.font(32.0),
+                            localized(*input),
+                            self.text(StyleRole::GlyphLabel, TextSize::Glyph),
                             Label,
                             Pickable::IGNORE,
                         )],
@@ -139,9 +266,8 @@ impl Style {
             }
             None => {
                 builder.spawn((
-                    Text::new(input.to_string()),
-                    self.font(32.0),
-                    TextColor(self.text_color),
+                    localized(*input),
+                    self.text(StyleRole::Text, TextSize::Glyph),
                     Label,
                     Pickable::IGNORE,
                 ));
@@ -149,51 +275,90 @@ impl Style {
         }
     }
 
+    fn display_gamepad_glyph(
+        &self,
+        builder: &mut ChildSpawnerCommands<'_>,
+        set: &GlyphSet,
+        entry: &GlyphEntry,
+    ) {
+        let mut glyph = builder.spawn((
+            Node {
+                height: Val::Px(set.size.y as f32),
+                width: Val::Px(set.size.x as f32),
+                padding: UiRect::px(0.0, 0.0, 0.0, 2.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            set.to_node(entry),
+            Pickable::IGNORE,
+        ));
+
+        if let Some(label) = &entry.label {
+            glyph.with_child((
+                Text::new(label.clone()),
+                self.text(StyleRole::GlyphLabel, TextSize::GlyphLabel),
+                Label,
+                Pickable::IGNORE,
+            ));
+        }
+    }
+
+    /// Finds the glyph for an input as a sprite, for showing inputs in the world.
+    ///
+    /// returns: (Sprite, Size, Label)
+    pub fn input_sprite(
+        &self,
+        glyphs: &GamepadGlyphs,
+        input: &Input,
+    ) -> Option<(Sprite, Vec2, Option<Localized>)> {
+        if let Input::Gamepad(button) = input {
+            if let Some((set, entry)) = glyphs
+                .active_set()
+                .and_then(|set| set.glyph(*button).map(|entry| (set, entry)))
+            {
+                return Some((
+                    set.to_sprite(entry),
+                    set.size.as_vec2(),
+                    entry.label.clone().map(Localized::verbatim),
+                ));
+            }
+        }
+
+        let (index, size, display_text) = input_glyph_info(input)?;
+        Some((
+            self.icons.to_sprite(index),
+            size.as_vec2(),
+            display_text.then(|| Localized::from(*input)),
+        ))
+    }
+
     /// Loads state from a database, resorting to defaults on failure.
     pub fn from_database(db: &Database, asset_server: &AssetServer) -> Self {
         let font_path: String =
-            db.get_kv_table_direct_or_default(STYLE_DB_TABLE, "font", DEFAULT_FONT_PATH);
+            db.get_kv_table_direct_or_default(STYLE_DB_TABLE, FONT_DB_KEY, DEFAULT_FONT_PATH);
 
-        Self {
-            font: asset_server.load(font_path),
-            icons: Icons::new(asset_server, BUTTON_SPRITE_IMAGE_PATH),
-
-            background_color: db.get_kv_table_or_default(
-                STYLE_DB_TABLE,
-                "background_color",
-                DEFAULT_BACKGROUND_COLOR,
-            ),
-            title_color: db.get_kv_table_or_default(
-                STYLE_DB_TABLE,
-                "title_color",
-                DEFAULT_TITLE_COLOR,
-            ),
-            text_color: db.get_kv_table_or_default(
-                STYLE_DB_TABLE,
-                "text_color",
-                DEFAULT_TEXT_COLOR,
-            ),
-            button_color: db.get_kv_table_or_default(
-                STYLE_DB_TABLE,
-                "normal_button",
-                DEFAULT_BUTTON_COLOR,
-            ),
-            pressed_button_color: db.get_kv_table_or_default(
-                STYLE_DB_TABLE,
-                "pressed_button",
-                DEFAULT_PRESSED_BUTTON_COLOR,
-            ),
-            hovered_button_color: db.get_kv_table_or_default(
-                STYLE_DB_TABLE,
-                "hovered_button",
-                DEFAULT_HOVERED_BUTTON_COLOR,
-            ),
-            hovered_pressed_button_color: db.get_kv_table_or_default(
-                STYLE_DB_TABLE,
-                "hovered_pressed_button",
-                DEFAULT_HOVERED_PRESSED_BUTTON_COLOR,
-            ),
+        let mut style = Self::new(
+            asset_server.load(font_path),
+            Icons::new(asset_server, BUTTON_SPRITE_IMAGE_PATH),
+            &Theme::default(),
+        );
+
+        let custom_font: Option<PathBuf> =
+            db.get_kv_table_or_default(STYLE_DB_TABLE, CUSTOM_FONT_DB_KEY, None::<PathBuf>);
+        if let Some(path) = custom_font {
+            if let Err(err) = style.set_custom_font(path.clone(), asset_server) {
+                warn!("Failed to load the font {} with: {err}", path.display());
+            }
         }
+
+        for color in ThemeColor::ALL {
+            *style.color_mut(color) =
+                db.get_kv_table_or_default(STYLE_DB_TABLE, color.db_key(), style.color(color));
+        }
+
+        style
     }
 
     /// Syncs data to the database
@@ -202,36 +367,77 @@ impl Style {
         db: &Database,
         asset_server: &AssetServer,
     ) -> Result<(), crate::database::SetKvError> {
-        let asset_path = asset_server
-            .get_path(self.font.id())
-            .expect("The font should have a file path!")
-            .to_string();
-
-        db.set_kv_table_direct(STYLE_DB_TABLE, "font", asset_path.as_str())?;
-        db.set_kv_table(STYLE_DB_TABLE, "text_color", self.text_color)?;
-        db.set_kv_table(STYLE_DB_TABLE, "text_color", self.text_color)?;
-        db.set_kv_table(STYLE_DB_TABLE, "background_color", self.background_color)?;
-        db.set_kv_table(STYLE_DB_TABLE, "title_color", self.title_color)?;
-        db.set_kv_table(STYLE_DB_TABLE, "text_color", self.text_color)?;
-        db.set_kv_table(STYLE_DB_TABLE, "button_color", self.button_color)?;
-        db.set_kv_table(
-            STYLE_DB_TABLE,
-            "pressed_button_color",
-            self.pressed_button_color,
-        )?;
-        db.set_kv_table(
-            STYLE_DB_TABLE,
-            "hovered_button_color",
-            self.hovered_button_color,
-        )?;
-        db.set_kv_table(
-            STYLE_DB_TABLE,
-            "hovered_pressed_button_color",
-            self.hovered_pressed_button_color,
-        )?;
+        // Fonts loaded from disk have no asset path, and are stored as the custom font instead.
+        if let Some(asset_path) = asset_server.get_path(self.font.id()) {
+            db.set_kv_table_direct(STYLE_DB_TABLE, FONT_DB_KEY, asset_path.to_string().as_str())?;
+        }
+        db.set_kv_table(STYLE_DB_TABLE, CUSTOM_FONT_DB_KEY, self.custom_font.clone())?;
+        for color in ThemeColor::ALL {
+            db.set_kv_table(STYLE_DB_TABLE, color.db_key(), self.color(color))?;
+        }
 
         Ok(())
     }
+
+    fn new(font: Handle<Font>, icons: Icons, theme: &Theme) -> Self {
+        Self {
+            font,
+            custom_font: None,
+            icons,
+            background_color: theme.get(ThemeColor::Background),
+            title_color: theme.get(ThemeColor::Title),
+            text_color: theme.get(ThemeColor::Text),
+            button_color: theme.get(ThemeColor::Button),
+            pressed_button_color: theme.get(ThemeColor::PressedButton),
+            hovered_button_color: theme.get(ThemeColor::HoveredButton),
+            hovered_pressed_button_color: theme.get(ThemeColor::HoveredPressedButton),
+            conflict_button_color: theme.get(ThemeColor::ConflictButton),
+        }
+    }
+
+    pub fn color(&self, color: ThemeColor) -> Color {
+        match color {
+            ThemeColor::Background => self.background_color,
+            ThemeColor::Title => self.title_color,
+            ThemeColor::Text => self.text_color,
+            ThemeColor::Button => self.button_color,
+            ThemeColor::PressedButton => self.pressed_button_color,
+            ThemeColor::HoveredButton => self.hovered_button_color,
+            ThemeColor::HoveredPressedButton => self.hovered_pressed_button_color,
+            ThemeColor::ConflictButton => self.conflict_button_color,
+        }
+    }
+
+    pub fn color_mut(&mut self, color: ThemeColor) -> &mut Color {
+        match color {
+            ThemeColor::Background => &mut self.background_color,
+            ThemeColor::Title => &mut self.title_color,
+            ThemeColor::Text => &mut self.text_color,
+            ThemeColor::Button => &mut self.button_color,
+            ThemeColor::PressedButton => &mut self.pressed_button_color,
+            ThemeColor::HoveredButton => &mut self.hovered_button_color,
+            ThemeColor::HoveredPressedButton => &mut self.hovered_pressed_button_color,
+            ThemeColor::ConflictButton => &mut self.conflict_button_color,
+        }
+    }
+
+    /// The current colors as a theme, named after the preset they match if any.
+    pub fn theme(&self, presets: &ThemePresets) -> Theme {
+        let mut theme = Theme::from_colors(
+            "Custom",
+            ThemeColor::ALL.map(|color| (color, self.color(color))),
+        );
+        if let Some(preset) = presets.find(&theme) {
+            theme.name = presets.0[preset].name.clone();
+        }
+        theme
+    }
+
+    pub fn set_theme(&mut self, theme: &Theme) {
+        for color in ThemeColor::ALL {
+            *self.color_mut(color) = theme.get(color);
+        }
+    }
 }
 
 #[derive(Reflect)]
@@ -268,6 +474,16 @@ impl Icons {
             ..default()
         }
     }
+
+    pub fn to_sprite(&self, index: usize) -> Sprite {
+        Sprite::from_atlas_image(
+            self.image.clone(),
+            TextureAtlas {
+                layout: self.layout.clone(),
+                index,
+            },
+        )
+    }
 }
 
 /// All the of faint heart, look not upon here,
@@ -0,0 +1,199 @@
+//! The colors of the [`Style`], and the preset themes the player can pick from.
+use crate::prelude::*;
+use bevy::{color::HexColorError, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// The preset themes, with the default first.
+const THEME_PRESETS: [&str; 4] = [
+    include_str!("../assets/themes/default.ron"),
+    include_str!("../assets/themes/high_contrast.ron"),
+    include_str!("../assets/themes/light.ron"),
+    include_str!("../assets/themes/colorblind.ron"),
+];
+
+const DEFAULT_TEXT_COLOR: Color = Color::srgb_u8(0xe0, 0xde, 0xf4);
+const DEFAULT_BACKGROUND_COLOR: Color = Color::srgba_u8(0x26, 0x23, 0x3a, 0xaa);
+const DEFAULT_TITLE_COLOR: Color = Color::srgb_u8(0x26, 0x23, 0x3a);
+const DEFAULT_BUTTON_COLOR: Color = Color::srgb_u8(0x26, 0x23, 0x3a);
+const DEFAULT_PRESSED_BUTTON_COLOR: Color = Color::srgb_u8(0x9c, 0xcf, 0xd8);
+const DEFAULT_HOVERED_BUTTON_COLOR: Color = Color::srgb_u8(0x1f, 0x1d, 0x2e);
+const DEFAULT_HOVERED_PRESSED_BUTTON_COLOR: Color = Color::srgb_u8(0x1f, 0x1d, 0x2e);
+const DEFAULT_CONFLICT_BUTTON_COLOR: Color = Color::srgb_u8(0xeb, 0x6f, 0x92);
+
+/// Each of the colors in a theme.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, Reflect, Serialize, Deserialize)]
+#[reflect(Debug, Hash, PartialEq, Clone, Serialize, Deserialize)]
+pub enum ThemeColor {
+    Background,
+    Title,
+    Text,
+    Button,
+    PressedButton,
+    HoveredButton,
+    HoveredPressedButton,
+    /// The color of buttons that are in an invalid state, such as conflicting keybinds.
+    ConflictButton,
+}
+
+impl ThemeColor {
+    pub const ALL: [Self; 8] = [
+        Self::Background,
+        Self::Title,
+        Self::Text,
+        Self::Button,
+        Self::PressedButton,
+        Self::HoveredButton,
+        Self::HoveredPressedButton,
+        Self::ConflictButton,
+    ];
+
+    /// The key the color is stored under in the `Style` table.
+    pub fn db_key(self) -> &'static str {
+        match self {
+            Self::Background => "background_color",
+            Self::Title => "title_color",
+            Self::Text => "text_color",
+            Self::Button => "button_color",
+            Self::PressedButton => "pressed_button_color",
+            Self::HoveredButton => "hovered_button_color",
+            Self::HoveredPressedButton => "hovered_pressed_button_color",
+            Self::ConflictButton => "conflict_button_color",
+        }
+    }
+
+    fn default_color(self) -> Color {
+        match self {
+            Self::Background => DEFAULT_BACKGROUND_COLOR,
+            Self::Title => DEFAULT_TITLE_COLOR,
+            Self::Text => DEFAULT_TEXT_COLOR,
+            Self::Button => DEFAULT_BUTTON_COLOR,
+            Self::PressedButton => DEFAULT_PRESSED_BUTTON_COLOR,
+            Self::HoveredButton => DEFAULT_HOVERED_BUTTON_COLOR,
+            Self::HoveredPressedButton => DEFAULT_HOVERED_PRESSED_BUTTON_COLOR,
+            Self::ConflictButton => DEFAULT_CONFLICT_BUTTON_COLOR,
+        }
+    }
+}
+
+impl From<ThemeColor> for Localized {
+    fn from(color: ThemeColor) -> Self {
+        Localized::new(match color {
+            ThemeColor::Background => "theme_color.background",
+            ThemeColor::Title => "theme_color.title",
+            ThemeColor::Text => "theme_color.text",
+            ThemeColor::Button => "theme_color.button",
+            ThemeColor::PressedButton => "theme_color.pressed",
+            ThemeColor::HoveredButton => "theme_color.hovered",
+            ThemeColor::HoveredPressedButton => "theme_color.hovered_pressed",
+            ThemeColor::ConflictButton => "theme_color.conflict",
+        })
+    }
+}
+
+/// A named set of colors for the [`Style`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    colors: HashMap<ThemeColor, Color>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            name: "Default".into(),
+            colors: ThemeColor::ALL
+                .into_iter()
+                .map(|color| (color, color.default_color()))
+                .collect(),
+        }
+    }
+}
+
+impl Theme {
+    pub fn from_colors(
+        name: impl Into<String>,
+        colors: impl IntoIterator<Item = (ThemeColor, Color)>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            colors: colors.into_iter().collect(),
+        }
+    }
+
+    pub fn get(&self, color: ThemeColor) -> Color {
+        self.colors
+            .get(&color)
+            .copied()
+            .unwrap_or_else(|| color.default_color())
+    }
+
+    /// Parses a theme from its RON description, where the colors are hex.
+    pub fn from_ron(description: &str) -> Result<Self, ThemeError> {
+        let description = ron::from_str::<ThemeDescription>(description)?;
+        let colors = description
+            .colors
+            .into_iter()
+            .map(|(color, hex)| match Srgba::hex(&hex) {
+                Ok(value) => Ok((color, value.into())),
+                Err(err) => Err(ThemeError::Color(color, hex, err)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            name: description.name,
+            colors,
+        })
+    }
+}
+
+/// The contents of a theme's RON file.
+#[derive(Deserialize, Debug)]
+struct ThemeDescription {
+    name: String,
+    colors: HashMap<ThemeColor, String>,
+}
+
+#[derive(Error, Debug)]
+pub enum ThemeError {
+    #[error("Failed to parse theme with: {0}")]
+    Parse(#[from] ron::de::SpannedError),
+    #[error("The {0:?} color '{1}' is not a hex color: {2}")]
+    Color(ThemeColor, String, HexColorError),
+}
+
+/// Formats a color as hex, which is how the player edits them.
+pub fn color_to_hex(color: Color) -> String {
+    color.to_srgba().to_hex()
+}
+
+/// The preset themes the player can pick from, with the default first.
+#[derive(Resource, Debug)]
+pub struct ThemePresets(pub Vec<Theme>);
+
+impl Default for ThemePresets {
+    fn default() -> Self {
+        Self(
+            THEME_PRESETS
+                .into_iter()
+                .filter_map(|description| {
+                    Theme::from_ron(description)
+                        .inspect_err(|err| error!("Failed to load a preset theme with: {err}"))
+                        .ok()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl ThemePresets {
+    /// The index of the preset with the same colors as `theme`, if any.
+    pub fn find(&self, theme: &Theme) -> Option<usize> {
+        self.0.iter().position(|preset| {
+            ThemeColor::ALL
+                .into_iter()
+                .all(|color| preset.get(color) == theme.get(color))
+        })
+    }
+}
@@ -0,0 +1,160 @@
+//! The sizes of text, the fonts it is drawn with, and loading the player's own font.
+//!
+//! Text is drawn with the [`Style`]'s font, and each character that font is missing,
+//! such as those of other languages or key names, is drawn with one of the
+//! [`FallbackFonts`] that has it.
+use crate::embed_asset;
+use crate::prelude::*;
+use bevy::{prelude::*, text::CosmicFontSystem};
+use std::{path::Path, sync::Arc};
+use thiserror::Error;
+
+/// Anything bigger than this is surely not the font the player meant to pick.
+const MAX_FONT_FILE_SIZE: u64 = 32 * 1024 * 1024;
+/// The characters a font needs to have for the menus to be readable.
+const REQUIRED_CHARACTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+/// The fonts shipped with the game that cover many more characters than its own font.
+const FALLBACK_FONT_PATHS: [&str; 1] = ["assets/fonts/DejaVuSans/DejaVuSans.ttf"];
+
+pub struct TypographyPlugin;
+
+impl Plugin for TypographyPlugin {
+    fn build(&self, app: &mut App) {
+        embed_asset!(app, "assets/fonts/DejaVuSans/DejaVuSans.ttf");
+
+        app.add_systems(Startup, init_resource::<FallbackFonts>)
+            .add_systems(
+                Update,
+                register_fallback_fonts.run_if(resource_exists::<FallbackFonts>),
+            );
+    }
+}
+
+/// The named sizes of text, which are scaled along with the rest
+/// of the UI by the [`DisplaySettings`](crate::display::DisplaySettings)' UI scale.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component, Debug, PartialEq, Hash)]
+pub enum TextSize {
+    /// Notes in the corner of a menu.
+    Small,
+    /// The labels drawn on top of input glyphs.
+    GlyphLabel,
+    /// Status lines and other secondary text.
+    Caption,
+    /// The text inside of small widgets, like the value of a slider.
+    Compact,
+    /// Input names, sized to fit inside of the input glyphs.
+    Glyph,
+    /// Buttons, labels and most other text.
+    Body,
+    /// The headings of menus.
+    Title,
+}
+
+impl TextSize {
+    /// The size in logical pixels, before the UI is scaled.
+    pub fn px(self) -> f32 {
+        match self {
+            Self::Small => 18.0,
+            Self::GlyphLabel => 20.0,
+            Self::Caption => 24.0,
+            Self::Compact => 28.0,
+            Self::Glyph => 32.0,
+            Self::Body => 33.0,
+            Self::Title => 67.0,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum FontError {
+    #[error("Failed to read the font with: {0}")]
+    Read(#[from] std::io::Error),
+    #[error("The font is {0} bytes, which is too big")]
+    TooBig(u64),
+    #[error("The file is not a font: {0}")]
+    Parse(#[from] ttf_parser::FaceParsingError),
+    #[error("The font is missing the character '{0}'")]
+    MissingCharacter(char),
+}
+
+impl From<&FontError> for Localized {
+    fn from(err: &FontError) -> Self {
+        match err {
+            FontError::Read(err) => Localized::new("font_error.read")
+                .with("error", Localized::verbatim(err.to_string())),
+            FontError::TooBig(size) => Localized::new("font_error.too_big")
+                .with("size", Localized::verbatim(size.to_string())),
+            FontError::Parse(err) => Localized::new("font_error.parse")
+                .with("error", Localized::verbatim(err.to_string())),
+            FontError::MissingCharacter(character) => {
+                Localized::new("font_error.missing_character")
+                    .with("character", Localized::verbatim(character.to_string()))
+            }
+        }
+    }
+}
+
+/// Reads a font from disk, checking that the menus can be drawn with it.
+pub fn load_font_file(path: &Path) -> Result<Font, FontError> {
+    let size = std::fs::metadata(path)?.len();
+    if size > MAX_FONT_FILE_SIZE {
+        return Err(FontError::TooBig(size));
+    }
+
+    let data = std::fs::read(path)?;
+    {
+        let face = ttf_parser::Face::parse(&data, 0)?;
+        if let Some(missing) = REQUIRED_CHARACTERS
+            .chars()
+            .find(|character| face.glyph_index(*character).is_none())
+        {
+            return Err(FontError::MissingCharacter(missing));
+        }
+    }
+
+    Ok(Font {
+        data: Arc::new(data),
+    })
+}
+
+/// The fonts drawing each character the [`Style`]'s font is missing.
+#[derive(Resource, Debug)]
+pub struct FallbackFonts(pub Vec<Handle<Font>>);
+
+impl FromWorld for FallbackFonts {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self(
+            FALLBACK_FONT_PATHS
+                .into_iter()
+                .map(|path| asset_server.load(format!("embedded://{path}")))
+                .collect(),
+        )
+    }
+}
+
+/// Hands each fallback font to the text shaper as it loads.
+///
+/// Bevy only gives the shaper the fonts that text is drawn with, but the shaper looks
+/// through every font it has for the characters missing from the text's own font.
+fn register_fallback_fonts(
+    fallbacks: Res<FallbackFonts>,
+    fonts: Res<Assets<Font>>,
+    mut font_events: EventReader<AssetEvent<Font>>,
+    mut font_system: ResMut<CosmicFontSystem>,
+) {
+    for event in font_events.read() {
+        let AssetEvent::LoadedWithDependencies { id } = event else {
+            continue;
+        };
+
+        if !fallbacks.0.iter().any(|handle| handle.id() == *id) {
+            continue;
+        }
+
+        if let Some(font) = fonts.get(*id) {
+            font_system.0.db_mut().load_font_data(font.data.to_vec());
+        }
+    }
+}